@@ -7,7 +7,7 @@ use buffman_tree::{
 use quickcheck::{Arbitrary, Gen};
 use rand::{RngCore, SeedableRng, seq::SliceRandom};
 use rand_xoshiro::Xoshiro256PlusPlus;
-use std::{borrow::Borrow, collections::BTreeMap, hint::black_box, time::Duration};
+use std::{borrow::Borrow, collections::BTreeMap, hint::black_box, io, time::Duration};
 
 trait MapExt<Q: ?Sized, V> {
     fn get_longest_prefix(&self, key: &Q) -> Option<&V>;
@@ -46,6 +46,62 @@ fn bench<'a, T: FromIterator<(K, V)> + MapExt<Q, V>, K, Q: 'a + ?Sized, V: Clone
     (init, run, ret)
 }
 
+/// Last level cache misses of the calling thread in user space while running 'f',
+/// counted by the kernel where it exposes the hardware counter.
+#[cfg(target_os = "linux")]
+fn cache_misses<T>(f: impl FnOnce() -> T) -> (io::Result<u64>, T) {
+    // leading fields of the kernel's perf_event_attr, as of its first version
+    #[repr(C)]
+    #[derive(Default)]
+    struct Attr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+    const HARDWARE: u32 = 0;
+    const CACHE_MISSES: u64 = 3;
+    const DISABLED: u64 = 1 << 0;
+    const EXCLUDE_KERNEL: u64 = 1 << 5;
+    const EXCLUDE_HV: u64 = 1 << 6;
+    const ENABLE: libc::c_ulong = 0x2400;
+    const DISABLE: libc::c_ulong = 0x2401;
+
+    let attr = Attr {
+        kind: HARDWARE,
+        size: size_of::<Attr>() as u32,
+        config: CACHE_MISSES,
+        flags: DISABLED | EXCLUDE_KERNEL | EXCLUDE_HV,
+        ..Default::default()
+    };
+    let fd = unsafe { libc::syscall(libc::SYS_perf_event_open, &attr, 0, -1, -1, 0) };
+    if fd < 0 {
+        return (Err(io::Error::last_os_error()), f());
+    }
+    let fd = fd as libc::c_int;
+    unsafe { libc::ioctl(fd, ENABLE, 0) };
+    let ret = f();
+    unsafe { libc::ioctl(fd, DISABLE, 0) };
+    let mut count = 0u64;
+    let read = unsafe { libc::read(fd, (&raw mut count).cast(), size_of::<u64>()) };
+    let misses = match read {
+        8 => Ok(count),
+        _ => Err(io::Error::last_os_error()),
+    };
+    unsafe { libc::close(fd) };
+    (misses, ret)
+}
+#[cfg(not(target_os = "linux"))]
+fn cache_misses<T>(f: impl FnOnce() -> T) -> (io::Result<u64>, T) {
+    (Err(io::ErrorKind::Unsupported.into()), f())
+}
+
 #[test]
 fn performance() {
     let mut rng = Xoshiro256PlusPlus::seed_from_u64(0);
@@ -93,3 +149,40 @@ fn performance() {
     assert_eq!(btree.2, btrie.2);
     assert_eq!(btree.2, byte_trie.2);
 }
+
+/// Compare runs with and without the 'wide-handles' and 'smallvec' features
+/// to see the impact of the handle width and of inline prefixes.
+/// Leaves stay in their own slab either way.
+#[test]
+fn footprint() {
+    let mut rng = Xoshiro256PlusPlus::seed_from_u64(0);
+    let entries = Vec::from_iter((0..1 << 16).map(|x| {
+        (
+            Box::<[u8]>::from_iter((0..rng.next_u32() % 16).map(|_| rng.next_u32() as u8)),
+            x,
+        )
+    }));
+    let (init, run, ret) = bench::<Trie<u8, usize, ByteBranch<_>>, _, _, _>(
+        entries.clone(),
+        entries.iter().map(|(k, _)| k),
+        usize::wrapping_add,
+    );
+    let trie = Trie::<u8, usize, ByteBranch<_>>::from_iter(entries.clone());
+    let (misses, _) = cache_misses(|| {
+        entries
+            .iter()
+            .flat_map(|(key, _)| trie.get_longest_prefix(key))
+            .fold(0, |sum, &x| black_box(sum + x))
+    });
+    println!(
+        "bytebranch:  {} bytes, heap: {} bytes, build: {init:?}, lookups: {run:?}, cache misses: {}",
+        size_of::<ByteBranch<usize>>(),
+        trie.stats().heap_bytes,
+        misses.map_or_else(
+            |err| format!("unavailable ({err})"),
+            |misses| misses.to_string()
+        ),
+    );
+    println!("btreebranch: {} bytes", size_of::<BTreeBranch<u8, usize>>());
+    assert!(ret.is_some());
+}
@@ -0,0 +1,218 @@
+use crate::{Trie, branch::BTreeBranch};
+use std::fmt::{Debug, Display};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    Empty,
+    EmptyLabel {
+        rule: String,
+    },
+    /// '*' anywhere but as the leftmost label, or combined with '!'.
+    MisplacedWildcard {
+        rule: String,
+    },
+}
+impl Display for RuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty rule"),
+            Self::EmptyLabel { rule } => write!(f, "empty label in '{rule}'"),
+            Self::MisplacedWildcard { rule } => {
+                write!(f, "wildcard is not the leftmost label in '{rule}'")
+            }
+        }
+    }
+}
+impl std::error::Error for RuleError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RuleKind {
+    /// 'example.com' matches exactly 'example.com' as public suffix.
+    Exact,
+    /// '*.example.com' matches any single label left of 'example.com' as public suffix.
+    /// A lone '*' is the default rule, making the rightmost label of any host a public suffix.
+    Wildcard,
+    /// '!www.example.com' makes 'example.com' the public suffix for 'www.example.com',
+    /// overriding any other matching rule.
+    Exception,
+}
+
+struct Rules<V> {
+    labels: usize,
+    rules: [Option<(String, V)>; 3],
+}
+impl<V> Rules<V> {
+    fn get(&self, kind: RuleKind) -> Option<(&str, &V)> {
+        let (rule, value) = self.rules[kind as usize].as_ref()?;
+        Some((rule, value))
+    }
+}
+
+/// A successful [DomainTrie::lookup].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainMatch<'t, 'h, V> {
+    pub rule: &'t str,
+    pub kind: RuleKind,
+    pub value: &'t V,
+    /// Trailing labels of the host covered by the prevailing rule.
+    pub public_suffix: &'h str,
+    /// Public suffix plus one more label, unless the host is a public suffix itself.
+    pub registrable_domain: Option<&'h str>,
+}
+
+/// Suffix rules over domain names, in the format of the public suffix list.
+/// Labels are stored right to left, so rules share prefixes by their top level domains.
+/// Hosts and rules are compared label by label as given, without case folding.
+pub struct DomainTrie<V> {
+    trie: Trie<String, Rules<V>, BTreeBranch<String, Rules<V>>>,
+    len: usize,
+}
+impl<V> Default for DomainTrie<V> {
+    fn default() -> Self {
+        Self {
+            trie: Default::default(),
+            len: 0,
+        }
+    }
+}
+impl<V: Debug> Debug for DomainTrie<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.rules()).finish()
+    }
+}
+impl<V> DomainTrie<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    fn labels(host: &str) -> impl Iterator<Item = &str> {
+        host.strip_suffix('.').unwrap_or(host).rsplit('.')
+    }
+    fn parse(rule: &str) -> Result<(RuleKind, Vec<&str>), RuleError> {
+        let error = |f: fn(String) -> RuleError| Err(f(rule.to_owned()));
+        if rule == "*" {
+            return Ok((RuleKind::Wildcard, vec![]));
+        }
+        let (kind, domain) = if let Some(domain) = rule.strip_prefix("*.") {
+            (RuleKind::Wildcard, domain)
+        } else if let Some(domain) = rule.strip_prefix('!') {
+            (RuleKind::Exception, domain)
+        } else {
+            (RuleKind::Exact, rule)
+        };
+        if domain.is_empty() {
+            return Err(RuleError::Empty);
+        }
+        let labels = Vec::from_iter(Self::labels(domain));
+        if labels.contains(&"") {
+            return error(|rule| RuleError::EmptyLabel { rule });
+        }
+        if labels.contains(&"*") {
+            return error(|rule| RuleError::MisplacedWildcard { rule });
+        }
+        Ok((kind, labels))
+    }
+    pub fn insert(&mut self, rule: &str, value: V) -> Result<Option<V>, RuleError> {
+        let (kind, labels) = Self::parse(rule)?;
+        if self.trie.get(labels.iter().copied()).is_none() {
+            self.trie.insert(
+                labels.iter().map(|label| label.to_string()),
+                Rules {
+                    labels: labels.len(),
+                    rules: Default::default(),
+                },
+            );
+        }
+        let rules = self.trie.get_mut(labels).unwrap();
+        let old = rules.rules[kind as usize].replace((rule.to_owned(), value));
+        if old.is_none() {
+            self.len += 1;
+        }
+        Ok(old.map(|(_, v)| v))
+    }
+    pub fn remove(&mut self, rule: &str) -> Result<Option<V>, RuleError> {
+        let (kind, labels) = Self::parse(rule)?;
+        let Some(rules) = self.trie.get_mut(labels.iter().copied()) else {
+            return Ok(None);
+        };
+        let old = rules.rules[kind as usize].take();
+        if rules.rules.iter().all(Option::is_none) {
+            self.trie.remove(labels);
+        }
+        if old.is_some() {
+            self.len -= 1;
+        }
+        Ok(old.map(|(_, v)| v))
+    }
+    pub fn clear(&mut self) {
+        self.trie.clear();
+        self.len = 0;
+    }
+    /// Finds the prevailing rule for 'host', being the matching exception if any,
+    /// otherwise the matching rule covering the most labels.
+    pub fn lookup<'t, 'h>(&'t self, host: &'h str) -> Option<DomainMatch<'t, 'h, V>> {
+        let host = host.strip_suffix('.').unwrap_or(host);
+        let len = Self::labels(host).count();
+        let mut best: Option<(usize, RuleKind, &str, &V)> = None;
+        for rules in self.trie.get_prefixes(Self::labels(host)) {
+            let candidates = [
+                (RuleKind::Exception, rules.labels.checked_sub(1)),
+                (
+                    RuleKind::Wildcard,
+                    (len > rules.labels).then_some(rules.labels + 1),
+                ),
+                (RuleKind::Exact, Some(rules.labels)),
+            ];
+            for (kind, suffix) in candidates {
+                let (Some(suffix), Some((rule, value))) = (suffix, rules.get(kind)) else {
+                    continue;
+                };
+                let better =
+                    best.is_none_or(|(best_suffix, best_kind, _, _)| match (kind, best_kind) {
+                        (RuleKind::Exception, RuleKind::Exception) => suffix > best_suffix,
+                        (RuleKind::Exception, _) => true,
+                        (_, RuleKind::Exception) => false,
+                        // On ties the rule given for more labels wins, so '*' yields to any other.
+                        _ => suffix >= best_suffix,
+                    });
+                if better {
+                    best = Some((suffix, kind, rule, value));
+                }
+            }
+        }
+        let (suffix, kind, rule, value) = best?;
+        Some(DomainMatch {
+            rule,
+            kind,
+            value,
+            public_suffix: Self::suffix(host, suffix),
+            registrable_domain: (suffix < len).then(|| Self::suffix(host, suffix + 1)),
+        })
+    }
+    pub fn registrable_domain<'h>(&self, host: &'h str) -> Option<&'h str> {
+        self.lookup(host)?.registrable_domain
+    }
+    fn suffix(host: &str, labels: usize) -> &str {
+        match labels.checked_sub(1) {
+            None => &host[host.len()..],
+            Some(n) => host
+                .rmatch_indices('.')
+                .nth(n)
+                .map_or(host, |(i, _)| &host[i + 1..]),
+        }
+    }
+    pub fn rules(&self) -> impl Iterator<Item = (&str, &V)> {
+        self.trie.iter().flat_map(|rules| {
+            rules
+                .rules
+                .iter()
+                .flatten()
+                .map(|(rule, value)| (rule.as_str(), value))
+        })
+    }
+}
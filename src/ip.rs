@@ -0,0 +1,275 @@
+use crate::{Trie, branch::BitBranch};
+use std::{
+    collections::BinaryHeap,
+    fmt::{Debug, Display},
+    net::{AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr},
+    num::ParseIntError,
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpNetError {
+    Addr(AddrParseError),
+    Len(ParseIntError),
+    MissingLen,
+    TooLong { len: u8, max: u8 },
+}
+impl Display for IpNetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Addr(err) => write!(f, "invalid address: {err}"),
+            Self::Len(err) => write!(f, "invalid prefix length: {err}"),
+            Self::MissingLen => write!(f, "missing '/' and prefix length"),
+            Self::TooLong { len, max } => write!(f, "prefix length {len} exceeds {max}"),
+        }
+    }
+}
+impl std::error::Error for IpNetError {}
+
+/// An IPv4 or IPv6 network prefix, with all host bits cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IpNet {
+    addr: IpAddr,
+    len: u8,
+}
+impl IpNet {
+    pub fn new(addr: impl Into<IpAddr>, len: u8) -> Result<Self, IpNetError> {
+        let addr = addr.into();
+        let max = Self::max_len(&addr);
+        if len > max {
+            return Err(IpNetError::TooLong { len, max });
+        }
+        let addr = match addr {
+            IpAddr::V4(addr) => IpAddr::V4(Ipv4Addr::from_bits(
+                addr.to_bits() & u32::MAX.checked_shl((32 - len).into()).unwrap_or(0),
+            )),
+            IpAddr::V6(addr) => IpAddr::V6(Ipv6Addr::from_bits(
+                addr.to_bits() & u128::MAX.checked_shl((128 - len).into()).unwrap_or(0),
+            )),
+        };
+        Ok(Self { addr, len })
+    }
+    pub fn host(addr: impl Into<IpAddr>) -> Self {
+        let addr = addr.into();
+        Self {
+            len: Self::max_len(&addr),
+            addr,
+        }
+    }
+    fn max_len(addr: &IpAddr) -> u8 {
+        match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        }
+    }
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+    pub fn prefix_len(&self) -> u8 {
+        self.len
+    }
+    pub fn is_ipv4(&self) -> bool {
+        self.addr.is_ipv4()
+    }
+    pub fn contains(&self, addr: impl Into<IpAddr>) -> bool {
+        self.covers(&Self::host(addr))
+    }
+    pub fn covers(&self, other: &Self) -> bool {
+        self.addr.is_ipv4() == other.addr.is_ipv4()
+            && self.len <= other.len
+            && Self::new(other.addr, self.len).is_ok_and(|net| net == *self)
+    }
+    /// The prefix one bit shorter than this one, or [None] for the default route.
+    pub fn parent(&self) -> Option<Self> {
+        Some(Self::new(self.addr, self.len.checked_sub(1)?).unwrap())
+    }
+    /// The two halves of this prefix, or [None] for a single host.
+    pub fn children(&self) -> Option<(Self, Self)> {
+        let len = self.len;
+        if len == Self::max_len(&self.addr) {
+            return None;
+        }
+        let upper = match self.addr {
+            IpAddr::V4(addr) => IpAddr::V4(Ipv4Addr::from_bits(addr.to_bits() | 1 << (31 - len))),
+            IpAddr::V6(addr) => IpAddr::V6(Ipv6Addr::from_bits(addr.to_bits() | 1 << (127 - len))),
+        };
+        Some((
+            Self {
+                len: len + 1,
+                ..*self
+            },
+            Self {
+                addr: upper,
+                len: len + 1,
+            },
+        ))
+    }
+    fn bits(&self) -> impl use<> + Iterator<Item = bool> {
+        let (bits, width) = match self.addr {
+            IpAddr::V4(addr) => (u128::from(addr.to_bits()), 32),
+            IpAddr::V6(addr) => (addr.to_bits(), 128),
+        };
+        (0..self.len).map(move |i| bits >> (width - 1 - i) & 1 == 1)
+    }
+}
+impl Display for IpNet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.len)
+    }
+}
+impl FromStr for IpNet {
+    type Err = IpNetError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, len) = s.split_once('/').ok_or(IpNetError::MissingLen)?;
+        Self::new(
+            addr.parse::<IpAddr>().map_err(IpNetError::Addr)?,
+            len.parse().map_err(IpNetError::Len)?,
+        )
+    }
+}
+
+type Table<V> = Trie<bool, (IpNet, V), BitBranch<(IpNet, V)>>;
+
+/// Routing table mapping [IpNet]s to values with bit-granular longest prefix matching.
+pub struct IpPrefixTable<V> {
+    v4: Table<V>,
+    v6: Table<V>,
+}
+impl<V> Default for IpPrefixTable<V> {
+    fn default() -> Self {
+        Self {
+            v4: Default::default(),
+            v6: Default::default(),
+        }
+    }
+}
+impl<V: Debug> Debug for IpPrefixTable<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(net, v)| (net.to_string(), v)))
+            .finish()
+    }
+}
+impl<V> FromIterator<(IpNet, V)> for IpPrefixTable<V> {
+    fn from_iter<T: IntoIterator<Item = (IpNet, V)>>(iter: T) -> Self {
+        let mut this = Self::default();
+        for (net, v) in iter {
+            this.insert(net, v);
+        }
+        this
+    }
+}
+impl<V> IpPrefixTable<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    fn table(&self, ipv4: bool) -> &Table<V> {
+        if ipv4 { &self.v4 } else { &self.v6 }
+    }
+    fn table_mut(&mut self, ipv4: bool) -> &mut Table<V> {
+        if ipv4 { &mut self.v4 } else { &mut self.v6 }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.v4.is_empty() && self.v6.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.v4.len() + self.v6.len()
+    }
+    pub fn insert(&mut self, net: IpNet, value: V) -> Option<V> {
+        self.table_mut(net.is_ipv4())
+            .insert(net.bits(), (net, value))
+            .map(|(_, v)| v)
+    }
+    pub fn get(&self, net: &IpNet) -> Option<&V> {
+        let bits = Vec::from_iter(net.bits());
+        self.table(net.is_ipv4()).get(&bits).map(|(_, v)| v)
+    }
+    pub fn get_mut(&mut self, net: &IpNet) -> Option<&mut V> {
+        let bits = Vec::from_iter(net.bits());
+        self.table_mut(net.is_ipv4()).get_mut(&bits).map(|(_, v)| v)
+    }
+    pub fn remove(&mut self, net: &IpNet) -> Option<V> {
+        let bits = Vec::from_iter(net.bits());
+        self.table_mut(net.is_ipv4()).remove(&bits).map(|(_, v)| v)
+    }
+    pub fn clear(&mut self) {
+        self.v4.clear();
+        self.v6.clear();
+    }
+    /// Most specific stored prefix containing 'addr'.
+    pub fn longest_match(&self, addr: impl Into<IpAddr>) -> Option<(IpNet, &V)> {
+        let net = IpNet::host(addr);
+        let bits = Vec::from_iter(net.bits());
+        self.table(net.is_ipv4())
+            .get_deepest(&bits)
+            .map(|(net, v)| (*net, v))
+    }
+    /// All stored prefixes containing 'addr', from least to most specific.
+    pub fn all_matches(&self, addr: impl Into<IpAddr>) -> impl Iterator<Item = (IpNet, &V)> {
+        let net = IpNet::host(addr);
+        let bits = Vec::from_iter(net.bits());
+        self.table(net.is_ipv4())
+            .get_prefixes(&bits)
+            .into_iter()
+            .map(|(net, v)| (*net, v))
+    }
+    /// All stored prefixes covered by 'net', including 'net' itself.
+    pub fn covered_by(&self, net: &IpNet) -> impl Iterator<Item = (IpNet, &V)> {
+        let bits = Vec::from_iter(net.bits());
+        self.table(net.is_ipv4())
+            .subtrie(&bits)
+            .into_iter()
+            .flat_map(|subtrie| subtrie.iter())
+            .map(|(net, v)| (*net, v))
+    }
+    /// Whether any stored prefix covers 'net'.
+    pub fn is_covered(&self, net: &IpNet) -> bool {
+        let bits = Vec::from_iter(net.bits());
+        self.table(net.is_ipv4()).get_deepest(&bits).is_some()
+    }
+    /// Whether both halves of 'net' are stored with equal values,
+    /// so they could be replaced by 'net' itself without changing any lookup.
+    pub fn can_aggregate(&self, net: &IpNet) -> bool
+    where
+        V: PartialEq,
+    {
+        crate::bool_try!({
+            let (lower, upper) = net.children()?;
+            self.get(&lower)? == self.get(&upper)?
+                && self.get(net).is_none_or(|v| Some(v) == self.get(&lower))
+        })
+    }
+    /// Repeatedly replaces aggregatable sibling prefixes by their parent,
+    /// returning the number of prefixes removed.
+    pub fn aggregate(&mut self) -> usize
+    where
+        V: PartialEq,
+    {
+        let len = self.len();
+        let mut nets = BinaryHeap::from_iter(self.iter().map(|(net, _)| (net.prefix_len(), net)));
+        while let Some((_, net)) = nets.pop() {
+            if let Some(parent) = net.parent()
+                && self.can_aggregate(&parent)
+            {
+                let (lower, upper) = parent.children().unwrap();
+                let value = self.remove(&lower).unwrap();
+                self.remove(&upper);
+                self.insert(parent, value);
+                nets.push((parent.prefix_len(), parent));
+            }
+        }
+        len - self.len()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (IpNet, &V)> {
+        self.v4
+            .iter()
+            .chain(self.v6.iter())
+            .map(|(net, v)| (*net, v))
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (IpNet, &mut V)> {
+        self.v4
+            .iter_mut()
+            .chain(self.v6.iter_mut())
+            .map(|(net, v)| (*net, v))
+    }
+}
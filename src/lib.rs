@@ -1,5 +1,10 @@
 mod trie;
 pub use trie::*;
+pub mod domain;
+pub mod ip;
+pub mod path;
+pub mod router;
+pub mod string;
 pub mod testing;
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,144 @@
+use crate::{
+    Trie,
+    branch::{BTreeBranch, Branch},
+};
+use std::{
+    borrow::Cow,
+    fmt::Debug,
+    path::{Component, Path},
+};
+
+/// Something that can be split into path segments.
+pub trait PathKey {
+    /// Calls 'f' with the non-empty segments of 'self'.
+    fn with_segments<T>(
+        &self,
+        separator: char,
+        f: impl FnOnce(&mut dyn Iterator<Item = &str>) -> T,
+    ) -> T;
+}
+impl PathKey for str {
+    fn with_segments<T>(
+        &self,
+        separator: char,
+        f: impl FnOnce(&mut dyn Iterator<Item = &str>) -> T,
+    ) -> T {
+        f(&mut self.split(separator).filter(|segment| !segment.is_empty()))
+    }
+}
+/// Splits into [Path::components] regardless of separator,
+/// skipping [Component::RootDir] and lossily converting non UTF-8 segments.
+impl PathKey for Path {
+    fn with_segments<T>(&self, _: char, f: impl FnOnce(&mut dyn Iterator<Item = &str>) -> T) -> T {
+        let segments = Vec::from_iter(self.components().filter_map(|component| match component {
+            Component::RootDir | Component::CurDir => None,
+            component => Some(component.as_os_str().to_string_lossy()),
+        }));
+        f(&mut segments.iter().map(Cow::as_ref))
+    }
+}
+
+/// [Trie] keyed by the segments of hierarchical names, like paths or URL routes.
+pub struct PathTrie<V, B = BTreeBranch<String, V>> {
+    trie: Trie<String, V, B>,
+    separator: char,
+}
+impl<V, B> Default for PathTrie<V, B> {
+    fn default() -> Self {
+        Self::with_separator('/')
+    }
+}
+impl<V: Debug, B: Branch<String, V>> Debug for PathTrie<V, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+impl<'p, P: 'p + ?Sized + PathKey, V, B: Branch<String, V>> FromIterator<(&'p P, V)>
+    for PathTrie<V, B>
+{
+    fn from_iter<T: IntoIterator<Item = (&'p P, V)>>(iter: T) -> Self {
+        let mut this = Self::default();
+        for (path, value) in iter {
+            this.insert(path, value);
+        }
+        this
+    }
+}
+impl<V, B> PathTrie<V, B> {
+    pub fn with_separator(separator: char) -> Self {
+        Self {
+            trie: Default::default(),
+            separator,
+        }
+    }
+    pub fn separator(&self) -> char {
+        self.separator
+    }
+}
+impl<V, B: Branch<String, V>> PathTrie<V, B> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+    pub fn insert<P: ?Sized + PathKey>(&mut self, path: &P, value: V) -> Option<V> {
+        path.with_segments(self.separator, |segments| {
+            self.trie.insert(segments.map(str::to_owned), value)
+        })
+    }
+    pub fn clear(&mut self) {
+        self.trie.clear();
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (String, &V)> {
+        let separator = self.separator.to_string();
+        self.trie
+            .iter_with_keys()
+            .map(move |(key, value)| (key.join(&separator), value))
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.trie.iter_mut()
+    }
+    pub fn as_trie(&self) -> &Trie<String, V, B> {
+        &self.trie
+    }
+}
+impl<V, B: Branch<String, V> + Branch<String, V, str>> PathTrie<V, B> {
+    pub fn get<P: ?Sized + PathKey>(&self, path: &P) -> Option<&V> {
+        path.with_segments(self.separator, |segments| self.trie.get(segments))
+    }
+    pub fn get_mut<P: ?Sized + PathKey>(&mut self, path: &P) -> Option<&mut V> {
+        path.with_segments(self.separator, |segments| self.trie.get_mut(segments))
+    }
+    /// Value of the closest ancestor of 'path', including itself.
+    pub fn get_deepest<P: ?Sized + PathKey>(&self, path: &P) -> Option<&V> {
+        path.with_segments(self.separator, |segments| self.trie.get_deepest(segments))
+    }
+    pub fn remove<P: ?Sized + PathKey>(&mut self, path: &P) -> Option<V> {
+        path.with_segments(self.separator, |segments| self.trie.remove(segments))
+    }
+    /// Immediate child segments of 'path' leading to further entries.
+    pub fn children<'a, P: ?Sized + PathKey>(
+        &'a self,
+        path: &P,
+    ) -> impl use<'a, V, B, P> + Iterator<Item = &'a str> {
+        path.with_segments(self.separator, |segments| self.trie.subtrie(segments))
+            .into_iter()
+            .flat_map(|subtrie| subtrie.children())
+            .map(String::as_str)
+    }
+    /// All entries at or below 'path', with keys relative to it.
+    pub fn descendants<'a, P: ?Sized + PathKey>(
+        &'a self,
+        path: &P,
+    ) -> impl use<'a, V, B, P> + Iterator<Item = (String, &'a V)> {
+        let separator = self.separator;
+        path.with_segments(separator, |segments| self.trie.subtrie(segments))
+            .into_iter()
+            .flat_map(|subtrie| subtrie.iter_with_keys())
+            .map(move |(key, value)| (key.join(&*separator.to_string()), value))
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{SubTrie, Trie, branch::BTreeBranch};
+use std::{
+    fmt::{Debug, Display},
+    iter::once,
+};
+
+// Segments of a request path are never empty and never contain '/',
+// so those can stand in for parameter and catch-all segments inside the trie.
+const PARAM: &str = "";
+const CATCH_ALL: &str = "/";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteError {
+    /// ':' or '*' without a name following it.
+    EmptyName { template: String },
+    /// The same name was used for more than one parameter.
+    DuplicateName { template: String, name: String },
+    /// A catch-all segment was followed by further segments.
+    CatchAllNotLast { template: String },
+    /// A route of the same shape, up to parameter names, already exists.
+    Conflict { template: String, existing: String },
+}
+impl Display for RouteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptyName { template } => write!(f, "unnamed parameter in '{template}'"),
+            Self::DuplicateName { template, name } => {
+                write!(f, "parameter '{name}' used more than once in '{template}'")
+            }
+            Self::CatchAllNotLast { template } => {
+                write!(f, "catch-all is not the last segment in '{template}'")
+            }
+            Self::Conflict { template, existing } => {
+                write!(f, "'{template}' conflicts with existing route '{existing}'")
+            }
+        }
+    }
+}
+impl std::error::Error for RouteError {}
+
+#[derive(Debug)]
+struct Route<V> {
+    template: String,
+    names: Vec<String>,
+    value: V,
+}
+
+/// A successful [Router::match_path].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteMatch<'r, 'p, V> {
+    pub value: &'r V,
+    pub template: &'r str,
+    /// Captured '(name, value)' pairs in template order.
+    pub params: Vec<(&'r str, &'p str)>,
+}
+impl<'r, 'p, V> RouteMatch<'r, 'p, V> {
+    pub fn param(&self, name: &str) -> Option<&'p str> {
+        self.params
+            .iter()
+            .find_map(|(n, value)| (*n == name).then_some(*value))
+    }
+}
+
+/// Routes request paths to values using templates like '/users/:id/posts/*rest'.
+/// Where several templates match, static segments take priority over
+/// ':param' segments, which take priority over a trailing '*catch_all'
+/// (matching one or more segments), decided segment by segment from left to right.
+pub struct Router<V> {
+    trie: Trie<String, Route<V>, BTreeBranch<String, Route<V>>>,
+}
+impl<V> Default for Router<V> {
+    fn default() -> Self {
+        Self {
+            trie: Default::default(),
+        }
+    }
+}
+impl<V: Debug> Debug for Router<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.routes()).finish()
+    }
+}
+impl<V> Router<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+    fn segments(path: &str) -> impl Iterator<Item = (usize, &str)> {
+        path.split('/')
+            .scan(0, |start, segment| {
+                let ret = (*start, segment);
+                *start += segment.len() + 1;
+                Some(ret)
+            })
+            .filter(|(_, segment)| !segment.is_empty())
+    }
+    fn parse(template: &str) -> Result<(Vec<String>, Vec<String>), RouteError> {
+        let error = |f: fn(String) -> RouteError| Err(f(template.to_owned()));
+        let (mut key, mut names) = (vec![], Vec::<String>::new());
+        for (_, segment) in Self::segments(template) {
+            if key.last().is_some_and(|last| last == CATCH_ALL) {
+                return error(|template| RouteError::CatchAllNotLast { template });
+            }
+            let (marker, name) = if let Some(name) = segment.strip_prefix(':') {
+                (PARAM, name)
+            } else if let Some(name) = segment.strip_prefix('*') {
+                (CATCH_ALL, name)
+            } else {
+                key.push(segment.to_owned());
+                continue;
+            };
+            if name.is_empty() {
+                return error(|template| RouteError::EmptyName { template });
+            }
+            if names.iter().any(|n| n == name) {
+                return Err(RouteError::DuplicateName {
+                    template: template.to_owned(),
+                    name: name.to_owned(),
+                });
+            }
+            key.push(marker.to_owned());
+            names.push(name.to_owned());
+        }
+        Ok((key, names))
+    }
+    /// Adds a route for 'template', failing without changes should it be malformed
+    /// or only differ from an existing route by parameter names.
+    pub fn insert(&mut self, template: &str, value: V) -> Result<(), RouteError> {
+        let (key, names) = Self::parse(template)?;
+        if let Some(existing) = self.trie.get(&key) {
+            return Err(RouteError::Conflict {
+                template: template.to_owned(),
+                existing: existing.template.clone(),
+            });
+        }
+        let route = Route {
+            template: template.to_owned(),
+            names,
+            value,
+        };
+        self.trie.insert(key, route);
+        Ok(())
+    }
+    /// Removes the route of the same shape as 'template', up to parameter names.
+    pub fn remove(&mut self, template: &str) -> Result<Option<V>, RouteError> {
+        let (key, _) = Self::parse(template)?;
+        Ok(self.trie.remove(&key).map(|route| route.value))
+    }
+    pub fn match_path<'r, 'p>(&'r self, path: &'p str) -> Option<RouteMatch<'r, 'p, V>> {
+        let segments = Vec::from_iter(Self::segments(path));
+        let mut captures = vec![];
+        let route = Self::walk(
+            self.trie.subtrie::<str>([])?,
+            path,
+            &segments,
+            &mut captures,
+        )?;
+        Some(RouteMatch {
+            value: &route.value,
+            template: &route.template,
+            params: Vec::from_iter(route.names.iter().map(String::as_str).zip(captures)),
+        })
+    }
+    fn walk<'r, 'p>(
+        node: SubTrie<'r, String, Route<V>, BTreeBranch<String, Route<V>>>,
+        path: &'p str,
+        segments: &[(usize, &'p str)],
+        captures: &mut Vec<&'p str>,
+    ) -> Option<&'r Route<V>> {
+        let Some(&(start, segment)) = segments.first() else {
+            return node.value();
+        };
+        if let Some(next) = node.subtrie(once(segment))
+            && let Some(route) = Self::walk(next, path, &segments[1..], captures)
+        {
+            return Some(route);
+        }
+        if let Some(next) = node.subtrie(once(PARAM)) {
+            captures.push(segment);
+            if let Some(route) = Self::walk(next, path, &segments[1..], captures) {
+                return Some(route);
+            }
+            captures.pop();
+        }
+        let route = node.subtrie(once(CATCH_ALL))?.value()?;
+        let (last, segment) = segments.last().unwrap();
+        captures.push(&path[start..last + segment.len()]);
+        Some(route)
+    }
+    pub fn routes(&self) -> impl Iterator<Item = (&str, &V)> {
+        self.trie
+            .iter()
+            .map(|route| (route.template.as_str(), &route.value))
+    }
+}
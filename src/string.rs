@@ -0,0 +1,122 @@
+use crate::{
+    Trie,
+    branch::{BTreeBranch, Branch},
+};
+use std::fmt::Debug;
+
+/// Element type a [str] can be split into and losslessly reassembled from.
+pub trait StrElem: Sized + Clone + PartialEq {
+    fn with_elems<T>(s: &str, f: impl FnOnce(&[Self]) -> T) -> T;
+    /// Only ever called with whole keys, or suffixes following a [str] prefix,
+    /// so the elements always form valid UTF-8.
+    fn collect(elems: Vec<Self>) -> String;
+}
+impl StrElem for u8 {
+    fn with_elems<T>(s: &str, f: impl FnOnce(&[Self]) -> T) -> T {
+        f(s.as_bytes())
+    }
+    fn collect(elems: Vec<Self>) -> String {
+        String::from_utf8(elems).expect("reconstructed key is valid UTF-8")
+    }
+}
+impl StrElem for char {
+    fn with_elems<T>(s: &str, f: impl FnOnce(&[Self]) -> T) -> T {
+        f(&Vec::from_iter(s.chars()))
+    }
+    fn collect(elems: Vec<Self>) -> String {
+        String::from_iter(elems)
+    }
+}
+
+/// [Trie] keyed by strings, split into [StrElem]s.
+pub struct StringTrie<E, V, B = BTreeBranch<E, V>> {
+    trie: Trie<E, V, B>,
+}
+/// [StringTrie] keyed by the UTF-8 bytes of strings.
+pub type StrTrie<V, B = BTreeBranch<u8, V>> = StringTrie<u8, V, B>;
+/// [StringTrie] keyed by the [char]s of strings.
+pub type CharTrie<V, B = BTreeBranch<char, V>> = StringTrie<char, V, B>;
+
+impl<E, V, B> Default for StringTrie<E, V, B> {
+    fn default() -> Self {
+        Self {
+            trie: Default::default(),
+        }
+    }
+}
+impl<E: StrElem, V: Debug, B: Branch<E, V>> Debug for StringTrie<E, V, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+impl<'s, E: StrElem, V, B: Branch<E, V>> FromIterator<(&'s str, V)> for StringTrie<E, V, B> {
+    fn from_iter<T: IntoIterator<Item = (&'s str, V)>>(iter: T) -> Self {
+        let mut this = Self::default();
+        for (key, value) in iter {
+            this.insert(key, value);
+        }
+        this
+    }
+}
+impl<E: StrElem, V, B: Branch<E, V>> StringTrie<E, V, B> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+    pub fn insert(&mut self, key: &str, value: V) -> Option<V> {
+        E::with_elems(key, |key| self.trie.insert(key.iter().cloned(), value))
+    }
+    pub fn get(&self, key: &str) -> Option<&V> {
+        E::with_elems(key, |key| self.trie.get(key))
+    }
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        E::with_elems(key, |key| self.trie.get_mut(key))
+    }
+    /// Value of the longest key that is a prefix of 'key'.
+    pub fn get_deepest(&self, key: &str) -> Option<&V> {
+        E::with_elems(key, |key| self.trie.get_deepest(key))
+    }
+    pub fn get_deepest_mut(&mut self, key: &str) -> Option<&mut V> {
+        E::with_elems(key, |key| self.trie.get_deepest_mut(key))
+    }
+    pub fn remove(&mut self, key: &str) -> Option<V> {
+        E::with_elems(key, |key| self.trie.remove(key))
+    }
+    pub fn clear(&mut self) {
+        self.trie.clear();
+    }
+    /// Whether any key starts with 'prefix'.
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        E::with_elems(prefix, |prefix| self.trie.subtrie(prefix))
+            .is_some_and(|subtrie| !subtrie.is_empty())
+    }
+    /// All entries whose key starts with 'prefix', in key order.
+    pub fn with_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (String, &'a V)> {
+        E::with_elems(prefix, |elems| self.trie.subtrie(elems))
+            .into_iter()
+            .flat_map(|subtrie| subtrie.iter_with_keys())
+            .map(move |(suffix, value)| (prefix.to_owned() + &E::collect(suffix), value))
+    }
+    pub fn keys(&self) -> impl Iterator<Item = String> {
+        self.trie.keys().map(E::collect)
+    }
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.trie.iter()
+    }
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.trie.iter_mut()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (String, &V)> {
+        self.trie
+            .iter_with_keys()
+            .map(|(key, value)| (E::collect(key), value))
+    }
+    pub fn as_trie(&self) -> &Trie<E, V, B> {
+        &self.trie
+    }
+}
@@ -3,6 +3,8 @@
 mod action;
 mod btrie;
 mod procedure;
+mod shape;
 pub use action::*;
 pub use btrie::*;
 pub use procedure::*;
+pub use shape::*;
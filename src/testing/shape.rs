@@ -0,0 +1,9 @@
+/// Layout of the nodes of a [Trie](crate::Trie), independent of where they were allocated,
+/// so that tries can be checked for being built the same way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shape<'a, K, V> {
+    pub prefix: Vec<K>,
+    pub value: Option<&'a V>,
+    /// Children in ascending order of their keys.
+    pub children: Vec<(K, Shape<'a, K, V>)>,
+}
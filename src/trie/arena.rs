@@ -0,0 +1,24 @@
+#![cfg(feature = "allocator-api2")]
+
+use crate::trie::slots::{Slot, SlotVec, Slots};
+use allocator_api2::{alloc::Allocator, vec::Vec};
+
+/// [Slots] allocated from 'A'.
+pub type Arena<T, A> = Slots<T, Vec<Slot<T>, A>>;
+
+impl<T, A: Allocator> SlotVec<T> for Vec<T, A> {
+    fn push(&mut self, val: T) {
+        Vec::push(self, val)
+    }
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+    fn capacity(&self) -> usize {
+        Vec::capacity(self)
+    }
+}
+impl<T, A: Allocator> Arena<T, A> {
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self::with_slots(Vec::with_capacity_in(capacity, alloc))
+    }
+}
@@ -0,0 +1,355 @@
+use crate::trie::{
+    Trie,
+    branch::{BTreeBranch, Branch, OrderedBranch},
+    node::NodeHandle,
+    vnode::{Observer, VNode},
+};
+use std::{
+    borrow::Borrow,
+    fmt::Debug,
+    marker::PhantomData,
+    mem::replace,
+    ops::{Bound, RangeBounds},
+};
+
+/// Summary over a set of values, like a sum, maximum or count.
+///
+/// 'combine' has to be associative with [Aggregate::empty] as its identity,
+/// but doesn't need to be commutative.
+pub trait Aggregate<V>: Clone {
+    /// Summary of no values at all.
+    fn empty() -> Self;
+    fn of(value: &V) -> Self;
+    fn combine(&self, other: &Self) -> Self;
+}
+
+/// How [Summaries] are computed from the values of a [Trie].
+pub(super) trait Summarize<V> {
+    type Summary: Clone;
+    fn of(&self, value: &V) -> Self::Summary;
+    fn combine(&self, a: &Self::Summary, b: &Self::Summary) -> Self::Summary;
+}
+struct ByAggregate<A>(PhantomData<fn() -> A>);
+impl<V, A: Aggregate<V>> Summarize<V> for ByAggregate<A> {
+    type Summary = A;
+    fn of(&self, value: &V) -> A {
+        A::of(value)
+    }
+    fn combine(&self, a: &A, b: &A) -> A {
+        a.combine(b)
+    }
+}
+
+/// Summary of every non-empty subtree of a [Trie], indexed like its nodes.
+/// Kept in sync by observing how [VNode] splits, contracts and frees nodes,
+/// while [Summaries::insert] and [Summaries::remove] recompute the affected path.
+pub(super) struct Summaries<S>(Vec<Option<S>>);
+impl<S> Default for Summaries<S> {
+    fn default() -> Self {
+        Self(vec![])
+    }
+}
+impl<K, V, B, S> Observer<K, V, B> for Summaries<S> {
+    fn moved(&mut self, from: &NodeHandle<K, V, B>, to: &NodeHandle<K, V, B>) {
+        let summary = self.set(from, None);
+        self.set(to, summary);
+    }
+    fn removed(&mut self, node: &NodeHandle<K, V, B>) {
+        self.set(node, None);
+    }
+}
+impl<S> Summaries<S> {
+    pub fn get<K, V, B>(&self, handle: &NodeHandle<K, V, B>) -> Option<&S> {
+        self.0.get(handle.leak()._unwrap())?.as_ref()
+    }
+    fn set<K, V, B>(&mut self, handle: &NodeHandle<K, V, B>, summary: Option<S>) -> Option<S> {
+        let index = handle.leak()._unwrap();
+        if self.0.len() <= index {
+            summary.as_ref()?;
+            self.0.resize_with(index + 1, || None);
+        }
+        replace(&mut self.0[index], summary)
+    }
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+impl<S: Clone> Summaries<S> {
+    /// Summaries of all of 'trie', computed from scratch.
+    pub fn new<K, V, B: Branch<K, V>>(
+        trie: &Trie<K, V, B>,
+        how: &impl Summarize<V, Summary = S>,
+    ) -> Self {
+        let mut this = Self::default();
+        let mut order = vec![];
+        let mut stack = vec![trie.root.leak()];
+        while let Some(handle) = stack.pop() {
+            if let Some(branch) = VNode::start(handle.leak()).skip_prefix(trie).branch(trie) {
+                stack.extend(Branch::<K, V>::values(branch));
+            }
+            order.push(handle);
+        }
+        // children always come after their parent
+        for handle in order.into_iter().rev() {
+            this.recompute(trie, how, &handle);
+        }
+        this
+    }
+    /// Recomputes the summary of 'handle' from its own value and the summaries of its children.
+    fn recompute<K, V, B: Branch<K, V>>(
+        &mut self,
+        trie: &Trie<K, V, B>,
+        how: &impl Summarize<V, Summary = S>,
+        handle: &NodeHandle<K, V, B>,
+    ) {
+        let node = VNode::start(handle.leak()).skip_prefix(trie);
+        let own = node.leaf(trie).map(|value| how.of(value));
+        let children = node
+            .branch(trie)
+            .into_iter()
+            .flat_map(Branch::<K, V>::values)
+            .filter_map(|child| self.get(&child));
+        // a node's own key comes before those of its children
+        let summary = children.fold(own, |summary, child| match summary {
+            Some(summary) => Some(how.combine(&summary, child)),
+            None => Some(child.clone()),
+        });
+        self.set(handle, summary);
+    }
+    pub fn insert<K: Clone + PartialEq, V, B: Branch<K, V>>(
+        &mut self,
+        trie: &mut Trie<K, V, B>,
+        how: &impl Summarize<V, Summary = S>,
+        key: impl IntoIterator<Item = K>,
+        value: V,
+    ) -> Option<V> {
+        let key = Vec::from_iter(key);
+        let ret = VNode::start(trie.root.leak())
+            .make_descend_observed(trie, key.iter().cloned(), self)
+            .make_leaf_observed(trie, value, self);
+        let mut path = vec![];
+        let target = VNode::start(trie.root.leak())
+            .descend(trie, &key, |vnode, _, _| {
+                path.push(vnode);
+                true
+            })
+            .unwrap_or_else(|_| unreachable!("just inserted"));
+        path.push(target);
+        for vnode in path.into_iter().rev() {
+            self.recompute(
+                trie,
+                how,
+                &vnode.skip_prefix(trie).is_node_handle(&trie.nodes),
+            );
+        }
+        ret
+    }
+    /// Like [Trie::remove], but recomputing summaries on the way back up while pruning.
+    pub fn remove<'a, K, V, B, Q: 'a + ?Sized + PartialEq>(
+        &mut self,
+        trie: &mut Trie<K, V, B>,
+        how: &impl Summarize<V, Summary = S>,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Option<V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V> + Branch<K, V, Q>,
+    {
+        let mut path = vec![];
+        let target = VNode::start(trie.root.leak())
+            .descend_mut(trie, key, |vnode, _, _| {
+                path.push(vnode);
+                true
+            })
+            .ok()?;
+        let (target, ret) = target.take_leaf(trie)?;
+        path.push(target);
+        for vnode in path.into_iter().rev() {
+            vnode.prune_branch_observed(trie, self);
+            self.recompute(
+                trie,
+                how,
+                &vnode.skip_prefix(trie).is_node_handle(&trie.nodes),
+            );
+        }
+        Some(ret)
+    }
+}
+
+/// [Trie] keeping an [Aggregate] of every subtree up to date,
+/// so that it can be queried for any prefix in O(depth).
+pub struct AugTrie<K, V, A, B = BTreeBranch<K, V>> {
+    trie: Trie<K, V, B>,
+    summaries: Summaries<A>,
+}
+impl<K, V, A, B> Default for AugTrie<K, V, A, B> {
+    fn default() -> Self {
+        Self {
+            trie: Default::default(),
+            summaries: Default::default(),
+        }
+    }
+}
+impl<K: Debug, V: Debug, A, B: Branch<K, V>> Debug for AugTrie<K, V, A, B>
+where
+    Trie<K, V, B>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("AugTrie").field(&self.trie).finish()
+    }
+}
+impl<K: Clone + PartialEq, V, A: Aggregate<V>, B: Branch<K, V>, I: IntoIterator<Item = K>>
+    FromIterator<(I, V)> for AugTrie<K, V, A, B>
+{
+    fn from_iter<T: IntoIterator<Item = (I, V)>>(iter: T) -> Self {
+        let mut this = Self::default();
+        for (key, value) in iter {
+            this.insert(key, value);
+        }
+        this
+    }
+}
+impl<K, V, A: Aggregate<V>, B: Branch<K, V>> AugTrie<K, V, A, B> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+    pub fn insert(&mut self, key: impl IntoIterator<Item = K>, value: V) -> Option<V>
+    where
+        K: Clone + PartialEq,
+    {
+        let how = ByAggregate::<A>(PhantomData);
+        self.summaries.insert(&mut self.trie, &how, key, value)
+    }
+    pub fn get<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        self.trie.get(key)
+    }
+    pub fn get_deepest<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        self.trie.get_deepest(key)
+    }
+    pub fn remove<'a, Q: 'a + ?Sized + PartialEq>(
+        &mut self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Option<V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let how = ByAggregate::<A>(PhantomData);
+        self.summaries.remove(&mut self.trie, &how, key)
+    }
+    pub fn clear(&mut self) {
+        self.trie.clear();
+        self.summaries.clear();
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &V> {
+        self.trie.iter()
+    }
+    pub fn as_trie(&self) -> &Trie<K, V, B> {
+        &self.trie
+    }
+    /// [Aggregate] of all values.
+    pub fn aggregate(&self) -> A {
+        self.summary(&self.trie.root)
+    }
+    /// [Aggregate] of all values whose keys start with 'prefix'.
+    pub fn aggregate_prefix<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        prefix: impl IntoIterator<Item = &'a Q>,
+    ) -> A
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        match VNode::start(self.trie.root.leak()).descend(&self.trie, prefix, |_, _, _| true) {
+            Ok(vnode) => self.summary(&self.handle(vnode)),
+            Err(_) => A::empty(),
+        }
+    }
+    /// [Aggregate] of all values whose keys lie within 'range', in lexicographic order.
+    ///
+    /// Only visits the nodes along the two bounds.
+    pub fn aggregate_range<R: ?Sized + Borrow<[K]>>(&self, range: impl RangeBounds<R>) -> A
+    where
+        K: Clone + Ord,
+        B: OrderedBranch<K, V>,
+    {
+        let bounds = (
+            range.start_bound().map(Borrow::borrow),
+            range.end_bound().map(Borrow::borrow),
+        );
+        self.aggregate_bounded(&self.trie.root, &mut vec![], bounds)
+    }
+    fn aggregate_bounded(
+        &self,
+        handle: &NodeHandle<K, V, B>,
+        key: &mut Vec<K>,
+        bounds: (Bound<&[K]>, Bound<&[K]>),
+    ) -> A
+    where
+        K: Clone + Ord,
+        B: OrderedBranch<K, V>,
+    {
+        let len = key.len();
+        key.extend_from_slice(handle.get(&self.trie.nodes).prefix());
+        // every key below starts with 'key', so they are all at least 'key'
+        // and less than any greater bound not starting with it
+        let above = |bound: &[K]| bound > &key[..] && !bound.starts_with(key);
+        let (starts_before, starts_after) = match bounds.0 {
+            Bound::Included(start) => (start <= &key[..], above(start)),
+            Bound::Excluded(start) => (start < &key[..], above(start)),
+            Bound::Unbounded => (true, false),
+        };
+        let (ends_after, ends_before) = match bounds.1 {
+            Bound::Included(end) => (above(end), end < &key[..]),
+            Bound::Excluded(end) => (above(end), end <= &key[..]),
+            Bound::Unbounded => (true, false),
+        };
+        let ret = if starts_after || ends_before {
+            A::empty()
+        } else if starts_before && ends_after {
+            self.summary(handle)
+        } else {
+            let node = VNode::start(handle.leak()).skip_prefix(&self.trie);
+            let mut ret = match node.leaf(&self.trie) {
+                Some(value) if bounds.contains(&key[..]) => A::of(value),
+                _ => A::empty(),
+            };
+            for (k, child) in node.branch(&self.trie).into_iter().flat_map(B::iter) {
+                key.push(k.clone());
+                ret = ret.combine(&self.aggregate_bounded(&child, key, bounds));
+                key.pop();
+            }
+            ret
+        };
+        key.truncate(len);
+        ret
+    }
+    fn summary(&self, handle: &NodeHandle<K, V, B>) -> A {
+        self.summaries.get(handle).cloned().unwrap_or_else(A::empty)
+    }
+    fn handle(&self, vnode: VNode<K, V, B>) -> NodeHandle<K, V, B> {
+        vnode
+            .skip_prefix(&self.trie)
+            .is_node_handle(&self.trie.nodes)
+    }
+}
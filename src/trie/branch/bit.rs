@@ -0,0 +1,140 @@
+use crate::{
+    NodeDebug,
+    branch::{Branch, HeapSize, OrderedBranch, Rebind},
+    trie::{Handle, Trie, node::NodeHandle, storage::Storage},
+    util::debug_fn,
+};
+use std::{array::from_fn, fmt::Debug, mem::replace};
+
+pub struct BitBranch<V> {
+    map: [NodeHandle<bool, V, Self>; 2],
+    #[cfg(feature = "testing")]
+    owner: NodeHandle<bool, V, Self>,
+}
+impl<V> Default for BitBranch<V> {
+    fn default() -> Self {
+        Self {
+            map: from_fn(|_| Handle::new_null()),
+            #[cfg(feature = "testing")]
+            owner: Handle::new_null(),
+        }
+    }
+}
+impl<V> Clone for BitBranch<V> {
+    fn clone(&self) -> Self {
+        Self {
+            map: from_fn(|k| self.map[k].leak()),
+            #[cfg(feature = "testing")]
+            owner: self.owner.leak(),
+        }
+    }
+}
+impl<V> HeapSize for BitBranch<V> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+impl<V> NodeDebug<bool, V, Self> for BitBranch<V> {
+    fn default_with_owner(#[cfg(feature = "testing")] owner: NodeHandle<bool, V, Self>) -> Self
+    where
+        Self: Default,
+    {
+        Self {
+            map: from_fn(|_| Handle::new_null()),
+            #[cfg(feature = "testing")]
+            owner,
+        }
+    }
+    fn debug<'a, S: Storage>(&'a self, trie: &'a Trie<bool, V, Self, S>) -> impl 'a + Debug
+    where
+        bool: Debug,
+        V: Debug,
+    {
+        debug_fn(|fmt| {
+            fmt.debug_map()
+                .entries(self.map.iter().enumerate().filter_map(|(k, node)| {
+                    Some((k != 0, node.get_null(&trie.nodes)?.debug(trie)))
+                }))
+                .finish()
+        })
+    }
+    #[cfg(feature = "testing")]
+    fn set_owner(&mut self, owner: NodeHandle<bool, V, Self>) -> NodeHandle<bool, V, Self> {
+        replace(&mut self.owner, owner)
+    }
+}
+impl<V> Branch<bool, V> for BitBranch<V> {
+    fn is_empty(&self) -> bool {
+        self.map.iter().all(|node| node.is_null())
+    }
+    fn insert(
+        &mut self,
+        key: bool,
+        node: NodeHandle<bool, V, Self>,
+    ) -> Option<NodeHandle<bool, V, Self>> {
+        replace(&mut self.map[key as usize], node).valid()
+    }
+    fn get_or_insert_with(
+        &mut self,
+        key: bool,
+        f: impl FnOnce() -> NodeHandle<bool, V, Self>,
+    ) -> NodeHandle<bool, V, Self> {
+        let node = &mut self.map[key as usize];
+        if let Some(node) = node.leak().valid() {
+            node
+        } else {
+            *node = f();
+            node.leak()
+        }
+    }
+    fn get(&self, key: &bool) -> Option<NodeHandle<bool, V, Self>> {
+        self.map[*key as usize].leak().valid()
+    }
+    fn cleanup(&mut self, mut f: impl FnMut(&mut NodeHandle<bool, V, Self>) -> bool) -> usize {
+        self.map
+            .iter_mut()
+            .map(|node| !f(node))
+            .filter(|kept| *kept)
+            .count()
+    }
+    fn prune(
+        &mut self,
+        f: impl FnMut(&mut NodeHandle<bool, V, Self>) -> bool,
+    ) -> Option<Option<(bool, NodeHandle<bool, V, Self>)>> {
+        match self.cleanup(f) {
+            0 => Some(None),
+            1 => Some(Some(
+                self.map
+                    .iter_mut()
+                    .enumerate()
+                    .find_map(|(k, node)| {
+                        node.is_valid()
+                            .then(|| (k != 0, replace(node, Handle::new_null())))
+                    })
+                    .unwrap(),
+            )),
+            2 => None,
+            3.. => unreachable!(),
+        }
+    }
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a bool, NodeHandle<bool, V, Self>)>
+    where
+        bool: 'a,
+    {
+        const KEYS: [bool; 2] = [false, true];
+        self.map
+            .iter()
+            .enumerate()
+            .filter_map(|(k, node)| node.leak().valid().map(|node| (&KEYS[k], node)))
+    }
+    fn into_entries(self) -> impl Iterator<Item = (bool, NodeHandle<bool, V, Self>)> {
+        self.map
+            .into_iter()
+            .enumerate()
+            .filter_map(|(k, node)| node.valid().map(|node| (k == 1, node)))
+    }
+}
+impl<V> OrderedBranch<bool, V> for BitBranch<V> {}
+impl<V, W> Rebind<bool, W> for BitBranch<V> {
+    type Branch = BitBranch<W>;
+}
@@ -1,9 +1,10 @@
 use crate::{
     NodeDebug,
-    branch::Branch,
+    branch::{Branch, HeapSize, OrderedBranch, Rebind},
     trie::{
         Handle, Trie,
         node::{Node, NodeHandle},
+        storage::Storage,
     },
     util::debug_fn,
 };
@@ -23,6 +24,25 @@ impl<K, V> Default for BTreeBranch<K, V> {
         }
     }
 }
+impl<K: Clone + Ord, V> Clone for BTreeBranch<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self
+                .map
+                .iter()
+                .map(|(k, v)| (k.clone(), v.leak()))
+                .collect(),
+            #[cfg(feature = "testing")]
+            owner: self.owner.leak(),
+        }
+    }
+}
+impl<K, V> HeapSize for BTreeBranch<K, V> {
+    fn heap_size(&self) -> usize {
+        // Ignores the per-node overhead of the underlying B-tree.
+        self.map.len() * (size_of::<K>() + size_of::<NodeHandle<K, V, Self>>())
+    }
+}
 impl<K, V> NodeDebug<K, V, Self> for BTreeBranch<K, V> {
     fn default_with_owner(#[cfg(feature = "testing")] owner: NodeHandle<K, V, Self>) -> Self {
         Self {
@@ -31,7 +51,7 @@ impl<K, V> NodeDebug<K, V, Self> for BTreeBranch<K, V> {
             owner,
         }
     }
-    fn debug<'a>(&'a self, trie: &'a Trie<K, V, Self>) -> impl 'a + Debug
+    fn debug<'a, S: Storage>(&'a self, trie: &'a Trie<K, V, Self, S>) -> impl 'a + Debug
     where
         K: Debug,
         V: Debug,
@@ -52,7 +72,7 @@ impl<K, V> NodeDebug<K, V, Self> for BTreeBranch<K, V> {
         replace(&mut self.owner, owner)
     }
 }
-impl<K: Ord + Borrow<Q>, V, Q: Ord> Branch<K, V, Q> for BTreeBranch<K, V> {
+impl<K: Ord + Borrow<Q>, V, Q: ?Sized + Ord> Branch<K, V, Q> for BTreeBranch<K, V> {
     fn is_empty(&self) -> bool {
         self.map.is_empty()
     }
@@ -90,4 +110,11 @@ impl<K: Ord + Borrow<Q>, V, Q: Ord> Branch<K, V, Q> for BTreeBranch<K, V> {
     {
         self.map.iter().map(|(k, v)| (k, v.leak()))
     }
+    fn into_entries(self) -> impl Iterator<Item = (K, NodeHandle<K, V, Self>)> {
+        self.map.into_iter()
+    }
+}
+impl<K: Ord, V> OrderedBranch<K, V> for BTreeBranch<K, V> {}
+impl<K: Ord, V, W> Rebind<K, W> for BTreeBranch<K, V> {
+    type Branch = BTreeBranch<K, W>;
 }
@@ -1,7 +1,7 @@
 use crate::{
     NodeDebug,
-    branch::Branch,
-    trie::{Handle, Trie, node::NodeHandle},
+    branch::{Branch, HeapSize, OrderedBranch, Rebind},
+    trie::{Handle, Trie, node::NodeHandle, storage::Storage},
     util::debug_fn,
 };
 use std::{array::from_fn, fmt::Debug, mem::replace};
@@ -20,6 +20,20 @@ impl<V> Default for ByteBranch<V> {
         }
     }
 }
+impl<V> Clone for ByteBranch<V> {
+    fn clone(&self) -> Self {
+        Self {
+            map: std::array::from_fn(|k| self.map[k].leak()),
+            #[cfg(feature = "testing")]
+            owner: self.owner.leak(),
+        }
+    }
+}
+impl<V> HeapSize for ByteBranch<V> {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
 impl<V> NodeDebug<u8, V, Self> for ByteBranch<V> {
     fn default_with_owner(#[cfg(feature = "testing")] owner: NodeHandle<u8, V, Self>) -> Self
     where
@@ -31,7 +45,7 @@ impl<V> NodeDebug<u8, V, Self> for ByteBranch<V> {
             owner,
         }
     }
-    fn debug<'a>(&'a self, trie: &'a Trie<u8, V, Self>) -> impl 'a + Debug
+    fn debug<'a, S: Storage>(&'a self, trie: &'a Trie<u8, V, Self, S>) -> impl 'a + Debug
     where
         u8: Debug,
         V: Debug,
@@ -124,4 +138,14 @@ impl<V> Branch<u8, V> for ByteBranch<V> {
             .enumerate()
             .filter_map(|(k, node)| node.leak().valid().map(|node| (&KEYS[k], node)))
     }
+    fn into_entries(self) -> impl Iterator<Item = (u8, NodeHandle<u8, V, Self>)> {
+        self.map
+            .into_iter()
+            .enumerate()
+            .filter_map(|(k, node)| node.valid().map(|node| (k as u8, node)))
+    }
+}
+impl<V> OrderedBranch<u8, V> for ByteBranch<V> {}
+impl<V, W> Rebind<u8, W> for ByteBranch<V> {
+    type Branch = ByteBranch<W>;
 }
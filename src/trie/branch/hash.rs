@@ -1,9 +1,10 @@
 use crate::{
     NodeDebug,
-    branch::Branch,
+    branch::{Branch, HeapSize, Rebind},
     trie::{
         Handle, Trie,
         node::{Node, NodeHandle},
+        storage::Storage,
     },
     util::debug_fn,
 };
@@ -29,6 +30,23 @@ impl<K, V, S: Default> Default for HashBranch<K, V, S> {
         }
     }
 }
+impl<K: Clone + Hash + Eq, V, S: Clone + BuildHasher> Clone for HashBranch<K, V, S> {
+    fn clone(&self) -> Self {
+        let mut map = HashMap::with_capacity_and_hasher(self.map.len(), self.map.hasher().clone());
+        map.extend(self.map.iter().map(|(k, v)| (k.clone(), v.leak())));
+        Self {
+            map,
+            #[cfg(feature = "testing")]
+            owner: self.owner.leak(),
+        }
+    }
+}
+impl<K, V, S> HeapSize for HashBranch<K, V, S> {
+    fn heap_size(&self) -> usize {
+        // One control byte per bucket besides the entry itself.
+        self.map.capacity() * (size_of::<(K, NodeHandle<K, V, Self>)>() + 1)
+    }
+}
 impl<K, V, S: Default> NodeDebug<K, V, Self> for HashBranch<K, V, S> {
     fn default_with_owner(#[cfg(feature = "testing")] owner: NodeHandle<K, V, Self>) -> Self {
         Self {
@@ -37,7 +55,7 @@ impl<K, V, S: Default> NodeDebug<K, V, Self> for HashBranch<K, V, S> {
             owner,
         }
     }
-    fn debug<'a>(&'a self, trie: &'a Trie<K, V, Self>) -> impl 'a + Debug
+    fn debug<'a, St: Storage>(&'a self, trie: &'a Trie<K, V, Self, St>) -> impl 'a + Debug
     where
         K: Debug,
         V: Debug,
@@ -58,7 +76,7 @@ impl<K, V, S: Default> NodeDebug<K, V, Self> for HashBranch<K, V, S> {
         replace(&mut self.owner, owner)
     }
 }
-impl<K: Hash + Eq + Borrow<Q>, V, Q: Hash + Eq, S: Default + BuildHasher> Branch<K, V, Q>
+impl<K: Hash + Eq + Borrow<Q>, V, Q: ?Sized + Hash + Eq, S: Default + BuildHasher> Branch<K, V, Q>
     for HashBranch<K, V, S>
 {
     fn is_empty(&self) -> bool {
@@ -97,4 +115,10 @@ impl<K: Hash + Eq + Borrow<Q>, V, Q: Hash + Eq, S: Default + BuildHasher> Branch
     {
         self.map.iter().map(|(k, v)| (k, v.leak()))
     }
+    fn into_entries(self) -> impl Iterator<Item = (K, NodeHandle<K, V, Self>)> {
+        self.map.into_iter()
+    }
+}
+impl<K: Hash + Eq, V, W, S: Default + BuildHasher> Rebind<K, W> for HashBranch<K, V, S> {
+    type Branch = HashBranch<K, W, S>;
 }
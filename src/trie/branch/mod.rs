@@ -1,12 +1,14 @@
+mod bit;
 mod btree;
 mod byte;
 mod hash;
 use crate::{NodeDebug, trie::node::NodeHandle};
+pub use bit::*;
 pub use btree::*;
 pub use byte::*;
 pub use hash::*;
 
-pub trait Branch<K, V, Q = K>: Sized + Default + NodeDebug<K, V, Self> {
+pub trait Branch<K, V, Q: ?Sized = K>: Sized + Default + NodeDebug<K, V, Self> {
     fn is_empty(&self) -> bool;
     fn insert(&mut self, key: K, node: NodeHandle<K, V, Self>) -> Option<NodeHandle<K, V, Self>>;
     fn get_or_insert_with(
@@ -24,6 +26,7 @@ pub trait Branch<K, V, Q = K>: Sized + Default + NodeDebug<K, V, Self> {
     fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a K, NodeHandle<K, V, Self>)>
     where
         K: 'a;
+    fn into_entries(self) -> impl Iterator<Item = (K, NodeHandle<K, V, Self>)>;
     fn keys<'a>(&'a self) -> impl Iterator<Item = &'a K>
     where
         K: 'a,
@@ -37,3 +40,16 @@ pub trait Branch<K, V, Q = K>: Sized + Default + NodeDebug<K, V, Self> {
         self.iter().map(|(_, v)| v)
     }
 }
+
+/// [Branch] whose [Branch::iter] yields children in ascending order of their keys.
+pub trait OrderedBranch<K, V>: Branch<K, V> {}
+
+/// The same kind of [Branch], but for values of type 'W'.
+pub trait Rebind<K, W> {
+    type Branch: Branch<K, W>;
+}
+
+/// Estimated bytes a [Branch] owns on the heap, excluding its keys' own allocations.
+pub trait HeapSize {
+    fn heap_size(&self) -> usize;
+}
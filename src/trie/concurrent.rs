@@ -0,0 +1,108 @@
+#![cfg(feature = "concurrent")]
+
+use crate::trie::{
+    PersistentTrie, Trie,
+    branch::{BTreeBranch, Branch},
+    storage::Pages,
+};
+use arc_swap::ArcSwap;
+use std::{
+    borrow::Borrow,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+/// [Trie] shared between threads, where readers never wait.
+///
+/// Every write publishes a new version of the [Trie], which shares all nodes, branches and leaves
+/// it didn't touch with the previous one, see [PersistentTrie].
+/// Readers always see the latest published version, writers take turns publishing new ones.
+pub struct ConcurrentTrie<K, V, B = BTreeBranch<K, V>> {
+    current: ArcSwap<PersistentTrie<K, V, B>>,
+    writer: Mutex<()>,
+}
+impl<K, V, B> Default for ConcurrentTrie<K, V, B> {
+    fn default() -> Self {
+        Self::from(PersistentTrie::default())
+    }
+}
+impl<K, V, B> From<PersistentTrie<K, V, B>> for ConcurrentTrie<K, V, B> {
+    fn from(trie: PersistentTrie<K, V, B>) -> Self {
+        Self {
+            current: ArcSwap::from_pointee(trie),
+            writer: Mutex::new(()),
+        }
+    }
+}
+impl<K: Clone + Debug, V: Debug, B: Branch<K, V>> Debug for ConcurrentTrie<K, V, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ConcurrentTrie")
+            .field(&self.current.load())
+            .finish()
+    }
+}
+impl<K, V, B> ConcurrentTrie<K, V, B> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// The current version, unaffected by any later updates.
+    pub fn snapshot(&self) -> Arc<PersistentTrie<K, V, B>> {
+        self.current.load_full()
+    }
+}
+impl<K, V: Clone, B: Branch<K, V>> ConcurrentTrie<K, V, B> {
+    pub fn is_empty(&self) -> bool {
+        self.current.load().is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.current.load().len()
+    }
+    pub fn get<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Option<V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        self.current.load().get(key).cloned()
+    }
+    pub fn get_deepest<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Option<V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        self.current.load().get_deepest(key).cloned()
+    }
+}
+impl<K: Clone, V: Clone, B: Clone + Branch<K, V>> ConcurrentTrie<K, V, B> {
+    pub fn insert(&self, key: impl IntoIterator<Item = K>, value: V) -> Option<V>
+    where
+        K: PartialEq,
+    {
+        self.update(|trie| trie.insert(key, value))
+    }
+    pub fn remove<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Option<V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        self.update(|trie| trie.remove(key))
+    }
+    /// Publishes the changes 'f' makes to the current version, with no other writer in between.
+    pub fn update<T>(&self, f: impl FnOnce(&mut Trie<K, V, B, Pages>) -> T) -> T {
+        let _writer = self
+            .writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (new, ret) = self.current.load().update(f);
+        self.current.store(Arc::new(new));
+        ret
+    }
+}
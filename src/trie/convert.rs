@@ -0,0 +1,67 @@
+use crate::trie::{
+    Trie,
+    branch::{Branch, Rebind},
+    handle::try_map_shared,
+};
+use std::convert::Infallible;
+
+impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
+    /// Moves every node into a trie of another value and branch type.
+    ///
+    /// Nodes and leaves keep their slab indices, so only the branches need to be rebuilt.
+    fn rebuild<W, B2: Branch<K, W>, E>(
+        self,
+        mut f: impl FnMut(V) -> Result<W, E>,
+    ) -> Result<Trie<K, W, B2>, E> {
+        let Self {
+            root,
+            nodes,
+            branches,
+            leaves,
+        } = self;
+        let leaves = try_map_shared(leaves, |leaf| leaf.try_map(&mut f))?;
+        let branches = try_map_shared(branches, |branch| {
+            let mut new = B2::default();
+            #[cfg(feature = "testing")]
+            let branch = {
+                let mut branch = branch;
+                new.set_owner(branch.set_owner(crate::trie::Handle::new_null()).cast());
+                branch
+            };
+            for (key, node) in Branch::<K, V>::into_entries(branch) {
+                new.insert(key, node.cast());
+            }
+            Ok::<_, Infallible>(new)
+        })
+        .unwrap_or_else(|never| match never {});
+        let nodes = try_map_shared(nodes, |node| Ok::<_, Infallible>(node.cast()))
+            .unwrap_or_else(|never| match never {});
+        Ok(Trie {
+            root: root.cast(),
+            nodes,
+            branches,
+            leaves,
+        })
+    }
+    pub fn map_values<W>(self, mut f: impl FnMut(V) -> W) -> Trie<K, W, B::Branch>
+    where
+        B: Rebind<K, W>,
+    {
+        self.rebuild(|value| Ok::<_, Infallible>(f(value)))
+            .unwrap_or_else(|never| match never {})
+    }
+    /// Stops at the first error, dropping the trie.
+    pub fn try_map_values<W, E>(
+        self,
+        f: impl FnMut(V) -> Result<W, E>,
+    ) -> Result<Trie<K, W, B::Branch>, E>
+    where
+        B: Rebind<K, W>,
+    {
+        self.rebuild(f)
+    }
+    pub fn convert_branch<B2: Branch<K, V>>(self) -> Trie<K, V, B2> {
+        self.rebuild(Ok::<_, Infallible>)
+            .unwrap_or_else(|never| match never {})
+    }
+}
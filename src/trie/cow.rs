@@ -0,0 +1,72 @@
+use crate::trie::{
+    Leaf, Node, Trie,
+    branch::BTreeBranch,
+    storage::{Pages, Storage},
+};
+use std::{
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+/// [Trie] handing out snapshots that stay unchanged while it continues to be mutated.
+///
+/// Taking a snapshot is O(1), mutating afterwards copies only the pages of nodes,
+/// branches and leaves it writes to, and only while a snapshot still shares them.
+pub type CowTrie<K, V, B = BTreeBranch<K, V>> = Trie<K, V, B, Pages>;
+
+/// Read-only view of a [Trie] as it was when [Trie::snapshot] was taken.
+pub struct Snapshot<K, V, B = BTreeBranch<K, V>, S: Storage = Pages>(Trie<K, V, B, S>);
+impl<K, V, B, S: Storage> Deref for Snapshot<K, V, B, S> {
+    type Target = Trie<K, V, B, S>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<K, V, B, S: Storage> Clone for Snapshot<K, V, B, S>
+where
+    Trie<K, V, B, S>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+impl<K, V, B, S: Storage> Debug for Snapshot<K, V, B, S>
+where
+    Trie<K, V, B, S>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+impl<K, V, B, S: Storage> Snapshot<K, V, B, S>
+where
+    Trie<K, V, B, S>: Clone,
+{
+    /// Mutable [Trie] starting out from the state of this snapshot.
+    pub fn to_trie(&self) -> Trie<K, V, B, S> {
+        self.0.clone()
+    }
+}
+
+impl<K, V, B, S: Storage> Trie<K, V, B, S>
+where
+    S::Store<Node<K, V, B>>: Clone,
+    S::Store<B>: Clone,
+    S::Store<Leaf<V>>: Clone,
+{
+    /// Read-only view of the current state, unaffected by any later mutation.
+    ///
+    /// O(1) for a [CowTrie], other storages copy all nodes, branches and leaves.
+    pub fn snapshot(&self) -> Snapshot<K, V, B, S> {
+        Snapshot(self.clone())
+    }
+}
+impl<K, V, B> Trie<K, V, B, Pages> {
+    /// Whether 'self' and 'other' still share all of their entries,
+    /// e.g. a snapshot that neither has been mutated since.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.nodes.ptr_eq(&other.nodes)
+            && self.branches.ptr_eq(&other.branches)
+            && self.leaves.ptr_eq(&other.leaves)
+    }
+}
@@ -0,0 +1,201 @@
+use crate::trie::{
+    Trie,
+    branch::Branch,
+    handle::Handle,
+    node::{DataHandle, Node, NodeHandle, Prefix},
+    vnode::VNode,
+};
+use std::{
+    borrow::Borrow,
+    fmt::{Debug, Display},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    /// Nothing to move starts with the source prefix.
+    NotFound,
+    /// Entries outside the source already start with the target prefix.
+    Occupied,
+}
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "no entries under source prefix"),
+            Self::Occupied => write!(f, "target prefix is already occupied"),
+        }
+    }
+}
+impl std::error::Error for MoveError {}
+
+impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
+    /// Detaches all entries starting with 'prefix' into a new [Trie], with 'prefix' stripped from their keys.
+    pub fn split_off_prefix<'a, Q: 'a + ?Sized + PartialEq>(
+        &mut self,
+        prefix: impl IntoIterator<Item = &'a Q>,
+    ) -> Self
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let mut other = Self::default();
+        let Ok((prefix, data)) = VNode::start(self.root.leak()).dive(
+            self,
+            prefix,
+            |_, _, _| true,
+            |node, this| {
+                let (node, prefix, data) = node.detach(this)?;
+                node.prune_branch(this);
+                Some((prefix, data))
+            },
+            |node, this, _| node.prune_branch(this),
+        ) else {
+            return other;
+        };
+        let root = other.root.leak();
+        root.get_mut(&mut other.nodes).prefix_mut().extend(prefix);
+        self.transplant(&mut other, root, data);
+        other
+    }
+    /// Inserts all entries of 'other' with 'prefix' prepended to their keys,
+    /// replacing already existing values.
+    pub fn graft(&mut self, prefix: impl IntoIterator<Item = K>, other: Self)
+    where
+        K: Clone + PartialEq,
+    {
+        self.graft_with(prefix, other, |old, new| *old = new);
+    }
+    /// Like [Trie::graft], but with 'merge' resolving keys present in both.
+    ///
+    /// Should nothing exist below 'prefix' yet, all of 'other' is moved over as is.
+    pub fn graft_with(
+        &mut self,
+        prefix: impl IntoIterator<Item = K>,
+        mut other: Self,
+        mut merge: impl FnMut(&mut V, V),
+    ) where
+        K: Clone + PartialEq,
+    {
+        if other.is_empty() {
+            return;
+        }
+        let vnode = VNode::start(self.root.leak()).make_descend(self, prefix);
+        if vnode.empty_node(&self.nodes) {
+            let node = vnode.is_node_handle(&self.nodes);
+            let (prefix, data) = other.root.get_mut(&mut other.nodes).take_data(0);
+            node.get_mut(&mut self.nodes).prefix_mut().extend(prefix);
+            other.transplant(self, node, data);
+            return;
+        }
+        let entries = Vec::from_iter(VNode::start(other.root.leak()).iter_keyed_handles(&other));
+        for (key, leaf) in entries {
+            let value = leaf.remove(&mut other.leaves).unwrap();
+            let node = vnode.make_descend(self, key);
+            match node.leaf_mut(self) {
+                Some(old) => merge(old, value),
+                None => {
+                    node.make_leaf(self, value);
+                }
+            }
+        }
+    }
+    /// Rewrites the keys of all entries starting with 'from' to start with 'to' instead.
+    ///
+    /// Only the nodes along both paths are touched, the moved entries themselves stay where they are.
+    /// On [MoveError::Occupied] nothing is changed.
+    pub fn move_prefix(
+        &mut self,
+        from: impl IntoIterator<Item = K>,
+        to: impl IntoIterator<Item = K>,
+    ) -> Result<(), MoveError>
+    where
+        K: PartialEq,
+    {
+        let from = Vec::from_iter(from);
+        let (prefix, data) = VNode::start(self.root.leak())
+            .dive(
+                self,
+                &from,
+                |_, _, _| true,
+                |node, this| {
+                    let (node, prefix, data) = node.detach(this)?;
+                    node.prune_branch(this);
+                    Some((prefix, data))
+                },
+                |node, this, _| node.prune_branch(this),
+            )
+            .map_err(|_| MoveError::NotFound)?;
+        let target = VNode::start(self.root.leak()).make_descend(self, to);
+        if target.empty_node(&self.nodes) {
+            self.attach(target.is_node_handle(&self.nodes), prefix, data);
+            Ok(())
+        } else {
+            let source = VNode::start(self.root.leak()).make_descend(self, from);
+            self.attach(source.is_node_handle(&self.nodes), prefix, data);
+            Err(MoveError::Occupied)
+        }
+    }
+    /// Attaches 'data' detached from inside 'self' to the empty 'node', see [VNode::detach].
+    fn attach(&mut self, node: NodeHandle<K, V, B>, prefix: Prefix<K>, data: DataHandle<V, B>) {
+        let (_leaf, branch) = data.leaf_branch();
+        if let Some(branch) = branch {
+            for child in Branch::<K, V>::values(branch.get(&self.branches)) {
+                child.get_mut(&mut self.nodes).set_previous(node.leak());
+            }
+            #[cfg(feature = "testing")]
+            branch.get_mut(&mut self.branches).set_owner(node.leak());
+        }
+        #[cfg(feature = "testing")]
+        if let Some(leaf) = _leaf {
+            crate::NodeDebug::<K, V, B>::set_owner(leaf.get_mut(&mut self.leaves), node.leak());
+        }
+        let target = node.get_mut(&mut self.nodes);
+        target.prefix_mut().extend(prefix);
+        let old = target.replace_data(data);
+        debug_assert!(matches!(old, DataHandle::Empty));
+    }
+    /// Moves 'data' detached from inside 'self' and everything below it into 'dest',
+    /// attaching it to the empty 'node'.
+    fn transplant(&mut self, dest: &mut Self, node: NodeHandle<K, V, B>, data: DataHandle<V, B>) {
+        let mut stack = vec![(node, data)];
+        while let Some((node, data)) = stack.pop() {
+            let (leaf, branch) = data.leaf_branch();
+            let marked = data.is_marked();
+            let leaf = leaf.map(|leaf| {
+                let leaf = leaf.remove(&mut self.leaves);
+                #[cfg(feature = "testing")]
+                let leaf = {
+                    let mut leaf = leaf;
+                    crate::NodeDebug::<K, V, B>::set_owner(&mut leaf, node.leak());
+                    leaf
+                };
+                Handle::new(&mut dest.leaves, leaf)
+            });
+            let branch = branch.map(|branch| {
+                let mut new = B::default_with_owner(
+                    #[cfg(feature = "testing")]
+                    node.leak(),
+                );
+                let branch = branch.remove(&mut self.branches);
+                for (key, child) in Branch::<K, V>::into_entries(branch) {
+                    let (prefix, data) = child.remove(&mut self.nodes).into_parts();
+                    let child = Handle::new_with(&mut dest.nodes, |_this| {
+                        Node::from(
+                            #[cfg(feature = "testing")]
+                            _this,
+                            node.leak(),
+                            prefix,
+                            (),
+                        )
+                    });
+                    stack.push((child.leak(), data));
+                    new.insert(key, child);
+                }
+                Handle::new(&mut dest.branches, new)
+            });
+            let old = node
+                .get_mut(&mut dest.nodes)
+                .replace_data(DataHandle::from((leaf, branch)).with_mark(marked));
+            debug_assert!(matches!(old, DataHandle::Empty));
+        }
+    }
+}
@@ -1,116 +1,225 @@
-use slab::Slab;
+use crate::trie::{slots::Slots, storage::Store};
 use std::{
     fmt::{Debug, Display},
     hash::Hash,
     marker::PhantomData,
-    usize,
 };
-pub type Shared<T> = Slab<T>;
-pub struct Handle<T>(usize, PhantomData<for<'a> fn(&'a T) -> &'a T>);
+pub type Shared<T> = Slots<T>;
+/// Index stored inside every [Handle], 'u32' unless the 'wide-handles' feature is enabled.
+#[cfg(not(feature = "wide-handles"))]
+pub type Index = u32;
+#[cfg(feature = "wide-handles")]
+pub type Index = usize;
+/// Most entries a single [Shared] can hold, as [Index::MAX] marks null handles.
+pub const CAPACITY: usize = widen(Index::MAX);
+
+/// Converts an [Index] back into the slot it refers to, which never truncates.
+#[cfg(not(feature = "wide-handles"))]
+const fn widen(index: Index) -> usize {
+    index as usize
+}
+#[cfg(feature = "wide-handles")]
+const fn widen(index: Index) -> usize {
+    index
+}
+
+/// Returned when a [Trie](super::Trie) would need more entries than its [Handle]s can address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError;
+impl Display for CapacityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "trie exceeds the capacity of its {}-bit handles",
+            Index::BITS
+        )
+    }
+}
+impl std::error::Error for CapacityError {}
+
+fn index(index: usize) -> Index {
+    match Index::try_from(index) {
+        Ok(index) if index != Index::MAX => index,
+        _ => panic!("{}", CapacityError),
+    }
+}
+/// Maps every entry of 'shared' while keeping them where they are, so that all handles stay valid.
+pub(crate) fn try_map_shared<T, U, E>(
+    shared: Shared<T>,
+    f: impl FnMut(T) -> Result<U, E>,
+) -> Result<Shared<U>, E> {
+    shared.try_map(f)
+}
+pub struct Handle<T>(
+    Index,
+    #[cfg(feature = "checked-handles")] u32,
+    PhantomData<for<'a> fn(&'a T) -> &'a T>,
+);
 impl<T> Handle<T> {
-    pub(crate) fn from(vacant: usize) -> Self {
-        Self(vacant, PhantomData)
+    /// Handle to the slot at 'index', as of its current generation.
+    fn at(_shared: &impl Store<T>, index: usize) -> Self {
+        Self(
+            self::index(index),
+            #[cfg(feature = "checked-handles")]
+            _shared.generation(index),
+            PhantomData,
+        )
+    }
+    fn check(&self, _shared: &impl Store<T>) {
+        #[cfg(feature = "checked-handles")]
+        _shared.check(widen(self.0), self.1);
     }
     pub(crate) fn _unwrap(self) -> usize {
-        self.0
+        widen(self.0)
+    }
+    pub(crate) fn cast<U>(self) -> Handle<U> {
+        Handle(
+            self.0,
+            #[cfg(feature = "checked-handles")]
+            self.1,
+            PhantomData,
+        )
     }
     pub fn new_shared() -> Shared<T> {
-        Slab::new()
+        Shared::new()
     }
     pub fn new_shared_with_capacity(capacity: usize) -> Shared<T> {
-        Slab::with_capacity(capacity)
+        Shared::with_capacity(capacity)
     }
     pub fn new_null() -> Self {
-        Self::from(usize::MAX)
-    }
-    pub fn _set(self, shared: &mut Slab<T>, val: T) -> Self {
+        Self(
+            Index::MAX,
+            #[cfg(feature = "checked-handles")]
+            0,
+            PhantomData,
+        )
+    }
+    pub fn _set(self, shared: &mut impl Store<T>, val: T) -> Self {
         assert!(self.is_null());
-        Self::from(shared.insert(val))
+        Self::new(shared, val)
     }
-    pub fn new(shared: &mut Slab<T>, val: T) -> Self {
-        Self::from(shared.insert(val))
+    pub fn new(shared: &mut impl Store<T>, val: T) -> Self {
+        let index = shared.insert(val);
+        Self::at(shared, index)
     }
-    pub fn new_with(shared: &mut Slab<T>, f: impl FnOnce(Self) -> T) -> Self {
-        let this = Self::from(shared.vacant_key());
+    pub fn new_with(shared: &mut impl Store<T>, f: impl FnOnce(Self) -> T) -> Self {
+        let this = Self::at(shared, shared.vacant_key());
         let ret = Self::new(shared, f(this.leak()));
         debug_assert_eq!(this, ret);
         ret
     }
-    pub fn _new_default(shared: &mut Slab<T>) -> Self
+    pub fn _new_default(shared: &mut impl Store<T>) -> Self
     where
         T: Default,
     {
         Self::new(shared, T::default())
     }
     pub fn is_null(&self) -> bool {
-        self.0 == usize::MAX
+        self.0 == Index::MAX
     }
     pub fn is_valid(&self) -> bool {
         !self.is_null()
     }
     pub fn leak(&self) -> Self {
-        Self::from(self.0)
+        Self(
+            self.0,
+            #[cfg(feature = "checked-handles")]
+            self.1,
+            PhantomData,
+        )
     }
     pub fn valid(self) -> Option<Self> {
         (!self.is_null()).then_some(self)
     }
-    pub fn get_null<'a>(&self, shared: &'a Slab<T>) -> Option<&'a T> {
+    pub fn get_null<'a>(&self, shared: &'a impl Store<T>) -> Option<&'a T> {
         (!self.is_null()).then(|| self.get(shared))
     }
-    pub fn get<'a>(&self, shared: &'a Slab<T>) -> &'a T {
-        &shared[self.0]
+    pub fn get<'a>(&self, shared: &'a impl Store<T>) -> &'a T {
+        self.check(shared);
+        shared.get(widen(self.0))
     }
-    pub fn get_mut_null<'a>(&self, shared: &'a mut Slab<T>) -> Option<&'a mut T> {
+    pub fn get_mut_null<'a>(&self, shared: &'a mut impl Store<T>) -> Option<&'a mut T> {
         (!self.is_null()).then(|| self.get_mut(shared))
     }
-    pub fn get_mut<'a>(&self, shared: &'a mut Slab<T>) -> &'a mut T {
-        &mut shared[self.0]
+    pub fn get_mut<'a>(&self, shared: &'a mut impl Store<T>) -> &'a mut T {
+        self.check(shared);
+        shared.get_mut(widen(self.0))
     }
-    pub fn _replace_null(&self, shared: &mut Slab<T>, val: T) -> Result<T, T> {
+    pub fn _replace_null(&self, shared: &mut impl Store<T>, val: T) -> Result<T, T> {
         if self.is_null() {
             Err(val)
         } else {
             Ok(self._replace(shared, val))
         }
     }
-    pub fn _replace(&self, shared: &mut Slab<T>, val: T) -> T {
+    pub fn _replace(&self, shared: &mut impl Store<T>, val: T) -> T {
         std::mem::replace(self.get_mut(shared), val)
     }
-    pub fn _remove_null(self, shared: &mut Slab<T>) -> Option<T> {
+    pub fn _remove_null(self, shared: &mut impl Store<T>) -> Option<T> {
         (!self.is_null()).then(|| self.remove(shared))
     }
-    pub fn remove(self, shared: &mut Slab<T>) -> T {
-        shared.remove(self.0)
+    pub fn remove(self, shared: &mut impl Store<T>) -> T {
+        self.check(shared);
+        shared.remove(widen(self.0))
+    }
+}
+impl<T> Handle<T> {
+    fn generation(&self) -> u32 {
+        #[cfg(feature = "checked-handles")]
+        return self.1;
+        #[cfg(not(feature = "checked-handles"))]
+        0
     }
 }
 impl<T> Debug for Handle<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_tuple("Handle").field(&self.0).finish()
+        let mut f = f.debug_tuple("Handle");
+        f.field(&self.0);
+        #[cfg(feature = "checked-handles")]
+        f.field(&self.1);
+        f.finish()
     }
 }
 impl<T> Display for Handle<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        <usize as Display>::fmt(&self.0, f)
+        <Index as Display>::fmt(&self.0, f)
     }
 }
 impl<T> PartialEq for Handle<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+        self.0 == other.0 && self.generation() == other.generation()
     }
 }
 impl<T> Eq for Handle<T> {}
 impl<T> PartialOrd for Handle<T> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.0.partial_cmp(&other.0)
+        Some(self.cmp(other))
     }
 }
 impl<T> Ord for Handle<T> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.0.cmp(&other.0)
+        (self.0, self.generation()).cmp(&(other.0, other.generation()))
     }
 }
 impl<T> Hash for Handle<T> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.0.hash(state);
+        self.generation().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn null_is_not_addressable() {
+        assert!(Handle::<()>::new_null().is_null());
+        assert_eq!(widen(index(CAPACITY - 1)), CAPACITY - 1);
+    }
+    #[test]
+    #[should_panic(expected = "exceeds the capacity")]
+    fn capacity_exceeded() {
+        index(CAPACITY);
     }
 }
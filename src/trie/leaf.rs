@@ -8,14 +8,23 @@ pub type LeafHandle<V> = Handle<Leaf<V>>;
 pub struct Leaf<V> {
     value: V,
     #[cfg(feature = "testing")]
-    owner: usize,
+    owner: Handle<()>,
+}
+impl<V: Clone> Clone for Leaf<V> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            #[cfg(feature = "testing")]
+            owner: self.owner.leak(),
+        }
+    }
 }
 impl<V: Default> Default for Leaf<V> {
     fn default() -> Self {
         Self {
             value: Default::default(),
             #[cfg(feature = "testing")]
-            owner: Handle::<()>::new_null()._unwrap(),
+            owner: Handle::new_null(),
         }
     }
 }
@@ -28,11 +37,14 @@ impl<K, V, B> NodeDebug<K, V, B> for Leaf<V> {
     {
         Self {
             #[cfg(feature = "testing")]
-            owner: owner._unwrap(),
+            owner: owner.cast(),
             ..Default::default()
         }
     }
-    fn debug<'a>(&'a self, _: &'a super::Trie<K, V, B>) -> impl 'a + std::fmt::Debug
+    fn debug<'a, S: super::storage::Storage>(
+        &'a self,
+        _: &'a super::Trie<K, V, B, S>,
+    ) -> impl 'a + std::fmt::Debug
     where
         K: std::fmt::Debug,
         V: std::fmt::Debug,
@@ -45,7 +57,7 @@ impl<K, V, B> NodeDebug<K, V, B> for Leaf<V> {
         owner: super::node::NodeHandle<K, V, B>,
     ) -> super::node::NodeHandle<K, V, B> {
         use std::mem::replace;
-        Handle::from(replace(&mut self.owner, owner._unwrap()))
+        replace(&mut self.owner, owner.cast()).cast()
     }
 }
 impl<V> Leaf<V> {
@@ -56,7 +68,7 @@ impl<V> Leaf<V> {
         Self {
             value,
             #[cfg(feature = "testing")]
-            owner: owner._unwrap(),
+            owner: owner.cast(),
         }
     }
     pub fn get(&self) -> &V {
@@ -71,6 +83,13 @@ impl<V> Leaf<V> {
     pub fn unwrap(self) -> V {
         self.value
     }
+    pub fn try_map<W, E>(self, f: impl FnOnce(V) -> Result<W, E>) -> Result<Leaf<W>, E> {
+        Ok(Leaf {
+            value: f(self.value)?,
+            #[cfg(feature = "testing")]
+            owner: self.owner,
+        })
+    }
     pub fn _take(&mut self) -> V
     where
         V: Default,
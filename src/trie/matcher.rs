@@ -0,0 +1,179 @@
+use crate::trie::{Trie, branch::Branch, vnode::VNode};
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, VecDeque},
+    iter,
+};
+
+/// Which matches [Matcher::find_iter] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchKind {
+    /// Every occurrence of every key, ordered by end, then by length descending.
+    #[default]
+    Overlapping,
+    /// Non-overlapping matches scanning left to right,
+    /// preferring the earliest start, then the longest key.
+    LeftmostLongest,
+}
+
+const ROOT: usize = 0;
+
+struct State<'a, K, V, B> {
+    vnode: VNode<K, V, B>,
+    depth: usize,
+    value: Option<&'a V>,
+    /// Longest proper suffix of this state that is also a state.
+    fail: usize,
+    /// Longest proper suffix of this state that has a value.
+    output: Option<usize>,
+}
+
+/// Aho-Corasick automaton over the keys of a [Trie].
+///
+/// States are positions inside the trie, including positions within compressed node prefixes,
+/// so building it only adds failure and output links on top of the existing structure.
+/// The empty key never matches.
+pub struct Matcher<'a, K, V, B> {
+    trie: &'a Trie<K, V, B>,
+    states: Vec<State<'a, K, V, B>>,
+    ids: HashMap<VNode<K, V, B>, usize>,
+    kind: MatchKind,
+}
+impl<K: PartialEq, V, B: Branch<K, V>> Trie<K, V, B> {
+    pub fn build_matcher(&self) -> Matcher<'_, K, V, B> {
+        Matcher::new(self)
+    }
+}
+impl<'a, K: PartialEq, V, B: Branch<K, V>> Matcher<'a, K, V, B> {
+    fn new(trie: &'a Trie<K, V, B>) -> Self {
+        let root = VNode::start(trie.root.leak());
+        let mut this = Self {
+            trie,
+            ids: HashMap::from([(root.leak(), ROOT)]),
+            states: vec![State {
+                vnode: root,
+                depth: 0,
+                value: None,
+                fail: ROOT,
+                output: None,
+            }],
+            kind: MatchKind::default(),
+        };
+        let mut queue = VecDeque::from([ROOT]);
+        while let Some(parent) = queue.pop_front() {
+            let children = Vec::from_iter(
+                this.states[parent]
+                    .vnode
+                    .child_keys(trie)
+                    .filter_map(|key| Some((key, this.states[parent].vnode.next(trie, key)?))),
+            );
+            for (key, vnode) in children {
+                let fail = match parent {
+                    ROOT => ROOT,
+                    _ => this.step(this.states[parent].fail, key),
+                };
+                let output = match this.states[fail].value {
+                    Some(_) if fail != ROOT => Some(fail),
+                    _ => this.states[fail].output,
+                };
+                let id = this.states.len();
+                this.ids.insert(vnode.leak(), id);
+                this.states.push(State {
+                    depth: this.states[parent].depth + 1,
+                    value: vnode.leaf(trie),
+                    vnode,
+                    fail,
+                    output,
+                });
+                queue.push_back(id);
+            }
+        }
+        this
+    }
+    pub fn match_kind(mut self, kind: MatchKind) -> Self {
+        self.kind = kind;
+        self
+    }
+    pub fn kind(&self) -> MatchKind {
+        self.kind
+    }
+    fn goto<Q: ?Sized + PartialEq>(&self, state: usize, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let vnode = self.states[state].vnode.next(self.trie, key)?;
+        self.ids.get(&vnode).copied()
+    }
+    fn step<Q: ?Sized + PartialEq>(&self, mut state: usize, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        loop {
+            if let Some(next) = self.goto(state, key) {
+                return next;
+            }
+            if state == ROOT {
+                return ROOT;
+            }
+            state = self.states[state].fail;
+        }
+    }
+    /// States ending at the current position that have a value, longest first.
+    fn outputs(&self, state: usize) -> impl use<'_, 'a, K, V, B> + Iterator<Item = usize> {
+        let first = match self.states[state].value {
+            Some(_) if state != ROOT => Some(state),
+            _ => self.states[state].output,
+        };
+        iter::successors(first, |&state| self.states[state].output)
+    }
+    /// Iterates over matches as '(start, end, value)', where 'haystack[start..end]' is the key.
+    pub fn find_iter<'h, Q: PartialEq>(
+        &self,
+        haystack: &'h [Q],
+    ) -> impl Iterator<Item = (usize, usize, &'a V)>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let mut pos = 0;
+        let mut state = ROOT;
+        let mut pending = None;
+        iter::from_fn(move || match self.kind {
+            MatchKind::Overlapping => loop {
+                if let Some(output) = pending {
+                    let output: &State<_, _, _> = &self.states[output];
+                    pending = output.output;
+                    return Some((pos - output.depth, pos, output.value?));
+                }
+                state = self.step(state, haystack.get(pos)?);
+                pos += 1;
+                pending = self.outputs(state).next();
+            },
+            MatchKind::LeftmostLongest => {
+                let mut best: Option<(usize, usize, &'a V)> = None;
+                let mut state = ROOT;
+                let mut end = pos;
+                while let Some(key) = haystack.get(end) {
+                    state = self.step(state, key);
+                    end += 1;
+                    for output in self.outputs(state) {
+                        let output = &self.states[output];
+                        let start = end - output.depth;
+                        if best.is_none_or(|(best, _, _)| start <= best) {
+                            best = output.value.map(|value| (start, end, value));
+                        }
+                    }
+                    // no live partial match can still start at or before the best one
+                    if best.is_some_and(|(start, _, _)| end - self.states[state].depth > start) {
+                        break;
+                    }
+                }
+                let best = best?;
+                pos = best.1;
+                Some(best)
+            }
+        })
+    }
+}
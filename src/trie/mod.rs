@@ -1,25 +1,61 @@
 use crate::{
     trie::{
         branch::{BTreeBranch, Branch},
-        handle::{Handle, Shared},
+        handle::Handle,
         leaf::{Leaf, LeafHandle},
-        node::{Node, NodeHandle},
+        node::{Node, NodeHandle, Prefix},
+        storage::Store,
         vnode::VNode,
     },
     util::opt_res_ext::OptExt as _,
 };
+mod arena;
+mod aug;
 pub mod branch;
+mod concurrent;
+mod convert;
+mod cow;
+mod graft;
 pub(self) mod handle;
 pub(self) mod leaf;
+mod matcher;
+mod multimap;
 pub(self) mod node;
+mod paged;
+mod par;
+mod persistent;
+mod prefix;
+mod score;
+mod set;
+mod shape;
+mod slots;
+mod stats;
+mod storage;
+mod subtrie;
 pub(self) mod vnode;
-use std::{borrow::Borrow, convert::identity, fmt::Debug};
+pub use aug::{Aggregate, AugTrie};
+#[cfg(feature = "concurrent")]
+pub use concurrent::ConcurrentTrie;
+pub use cow::{CowTrie, Snapshot};
+pub use graft::MoveError;
+pub use handle::CapacityError;
+pub use matcher::{MatchKind, Matcher};
+pub use multimap::TrieMultiMap;
+pub use persistent::PersistentTrie;
+pub use score::ScoreIndex;
+pub use set::TrieSet;
+pub use stats::{SlabStats, Stats};
+use std::{borrow::Borrow, convert::identity, fmt::Debug, iter};
+#[cfg(feature = "allocator-api2")]
+pub use storage::Arenas;
+pub use storage::{Pages, Slabs, Storage};
+pub use subtrie::SubTrie;
 
 pub trait NodeDebug<K, V, B> {
     fn default_with_owner(#[cfg(feature = "testing")] owner: NodeHandle<K, V, B>) -> Self
     where
         Self: Default;
-    fn debug<'a>(&'a self, trie: &'a Trie<K, V, B>) -> impl 'a + Debug
+    fn debug<'a, S: Storage>(&'a self, trie: &'a Trie<K, V, B, S>) -> impl 'a + Debug
     where
         K: Debug,
         V: Debug;
@@ -27,59 +63,96 @@ pub trait NodeDebug<K, V, B> {
     fn set_owner(&mut self, owner: NodeHandle<K, V, B>) -> NodeHandle<K, V, B>;
 }
 
-pub struct Trie<K, V, B = BTreeBranch<K, V>> {
+/// Result of a prefix search, see [Trie::get_deepest_with_len].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<T> {
+    /// Number of key elements consumed to reach 'value'.
+    pub depth: usize,
+    pub value: T,
+    /// Whether the whole key was consumed.
+    pub exact: bool,
+}
+impl<T> Match<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Match<U> {
+        Match {
+            depth: self.depth,
+            value: f(self.value),
+            exact: self.exact,
+        }
+    }
+}
+
+pub struct Trie<K, V, B = BTreeBranch<K, V>, S: Storage = Slabs> {
     root: NodeHandle<K, V, B>,
-    nodes: Shared<Node<K, V, B>>,
-    branches: Shared<B>,
-    leaves: Shared<Leaf<V>>,
+    nodes: S::Store<Node<K, V, B>>,
+    branches: S::Store<B>,
+    leaves: S::Store<Leaf<V>>,
 }
-impl<K: Debug, V: Debug, B: NodeDebug<K, V, B>> Debug for Trie<K, V, B> {
+impl<K: Debug, V: Debug, B: NodeDebug<K, V, B>, S: Storage> Debug for Trie<K, V, B, S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("Trie")
             .field(&self.root.get(&self.nodes).debug(&self))
             .finish()
     }
 }
-impl<K, V, B> Default for Trie<K, V, B> {
-    fn default() -> Self {
-        let mut nodes = Handle::new_shared();
+impl<K, V, B, S: Storage> Clone for Trie<K, V, B, S>
+where
+    S::Store<Node<K, V, B>>: Clone,
+    S::Store<B>: Clone,
+    S::Store<Leaf<V>>: Clone,
+{
+    fn clone(&self) -> Self {
         Self {
-            root: Handle::new_with(&mut nodes, |_this| {
-                Node::from(
-                    #[cfg(feature = "testing")]
-                    _this,
-                    Handle::new_null(),
-                    vec![],
-                    (),
-                )
-            }),
-            nodes,
-            branches: Handle::new_shared(),
-            leaves: Handle::new_shared(),
+            root: self.root.leak(),
+            nodes: self.nodes.clone(),
+            branches: self.branches.clone(),
+            leaves: self.leaves.clone(),
         }
     }
 }
-impl<K, V, B> Trie<K, V, B> {
+impl<K, V, B, S: Storage + Default> Default for Trie<K, V, B, S> {
+    fn default() -> Self {
+        Self::with_capacity(0)
+    }
+}
+impl<K, V, B, S: Storage + Default> Trie<K, V, B, S> {
     pub fn with_capacity(capacity: usize) -> Self {
-        let mut nodes = Handle::new_shared_with_capacity(capacity);
+        Self::with_storage(capacity, &S::default())
+    }
+}
+impl<K, V, B, S: Storage> Trie<K, V, B, S> {
+    fn with_storage(capacity: usize, storage: &S) -> Self {
+        let mut nodes = storage.store(capacity);
         Self {
             root: Handle::new_with(&mut nodes, |_this| {
                 Node::from(
                     #[cfg(feature = "testing")]
                     _this,
                     Handle::new_null(),
-                    vec![],
+                    Prefix::new(),
                     (),
                 )
             }),
             nodes,
-            branches: Handle::new_shared_with_capacity(capacity),
-            leaves: Handle::new_shared_with_capacity(capacity),
+            branches: storage.store(capacity),
+            leaves: storage.store(capacity),
         }
     }
 }
-impl<K: IntoIterator<Item: PartialEq>, V, B: Branch<K::Item, V>> FromIterator<(K, V)>
-    for Trie<K::Item, V, B>
+#[cfg(feature = "allocator-api2")]
+impl<K, V, B, A: allocator_api2::alloc::Allocator + Clone> Trie<K, V, B, Arenas<A>> {
+    /// Empty [Trie] allocating the slots of its nodes, branches and leaves from 'alloc'.
+    ///
+    /// Node prefixes and the maps inside branches still allocate from the global allocator.
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_capacity_in(0, alloc)
+    }
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self::with_storage(capacity, &Arenas(alloc))
+    }
+}
+impl<K: IntoIterator<Item: PartialEq>, V, B: Branch<K::Item, V>, S: Storage + Default>
+    FromIterator<(K, V)> for Trie<K::Item, V, B, S>
 {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
         let iter = iter.into_iter();
@@ -94,8 +167,8 @@ impl<K: IntoIterator<Item: PartialEq>, V, B: Branch<K::Item, V>> FromIterator<(K
         this
     }
 }
-impl<K: Clone + IntoIterator<Item: PartialEq>, V, B: Branch<K::Item, (K, V)>> FromIterator<(K, V)>
-    for Trie<K::Item, (K, V), B>
+impl<K: Clone + IntoIterator<Item: PartialEq>, V, B: Branch<K::Item, (K, V)>, S: Storage + Default>
+    FromIterator<(K, V)> for Trie<K::Item, (K, V), B, S>
 {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
         let iter = iter.into_iter();
@@ -110,12 +183,12 @@ impl<K: Clone + IntoIterator<Item: PartialEq>, V, B: Branch<K::Item, (K, V)>> Fr
         this
     }
 }
-impl<K: PartialEq, V: PartialEq, B: Branch<K, V>> PartialEq for Trie<K, V, B> {
+impl<K: PartialEq, V: PartialEq, B: Branch<K, V>, S: Storage> PartialEq for Trie<K, V, B, S> {
     fn eq(&self, other: &Self) -> bool {
         self.iter().eq(other.iter())
     }
 }
-impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
+impl<K, V, B: Branch<K, V>, S: Storage> Trie<K, V, B, S> {
     pub fn is_empty(&self) -> bool {
         let empty_shallow = self.root.get(&self.nodes).is_empty();
         debug_assert_eq!(
@@ -139,14 +212,36 @@ impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
             .make_descend(self, key)
             .make_leaf(self, value)
     }
-    pub fn get<'a, Q: 'a + PartialEq>(&self, key: impl IntoIterator<Item = &'a Q>) -> Option<&V>
+    /// Like [Trie::insert], but fails instead of panicking once the [Trie] would outgrow its handles.
+    pub fn try_insert(
+        &mut self,
+        key: impl IntoIterator<Item = K>,
+        value: V,
+    ) -> Result<Option<V>, CapacityError>
+    where
+        K: PartialEq,
+    {
+        // A single insertion splits at most one node and adds at most one child below it.
+        const RESERVE: usize = 2;
+        if [self.nodes.len(), self.branches.len(), self.leaves.len()]
+            .into_iter()
+            .any(|len| len > handle::CAPACITY - RESERVE)
+        {
+            return Err(CapacityError);
+        }
+        Ok(self.insert(key, value))
+    }
+    pub fn get<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Option<&V>
     where
         K: Borrow<Q>,
         B: Branch<K, V, Q>,
     {
         Some(self.get_handle(key)?.get(&self.leaves).get())
     }
-    pub fn get_mut<'a, Q: 'a + PartialEq>(
+    pub fn get_mut<'a, Q: 'a + ?Sized + PartialEq>(
         &mut self,
         key: impl IntoIterator<Item = &'a Q>,
     ) -> Option<&mut V>
@@ -156,7 +251,10 @@ impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
     {
         Some(self.get_handle(key)?.get_mut(&mut self.leaves).get_mut())
     }
-    pub fn try_get<'a, Q: 'a + PartialEq>(
+    /// The value of the longest key 'key' starts with inside [Result::Ok],
+    /// with [Result::Err] only ever holding [Option::None] when there is none.
+    /// See [Trie::try_get_exact] to tell exact matches apart.
+    pub fn try_get<'a, Q: 'a + ?Sized + PartialEq>(
         &self,
         key: impl IntoIterator<Item = &'a Q>,
     ) -> Result<&V, Option<&V>>
@@ -164,15 +262,25 @@ impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
         K: Borrow<Q>,
         B: Branch<K, V, Q>,
     {
-        Ok(self
-            .try_get_handle(key)
-            .map_err(Option::remap(|leaf: LeafHandle<V>| {
-                leaf.get(&self.leaves).get()
-            }))?
-            .get(&self.leaves)
-            .get())
-    }
-    pub fn try_get_mut<'a, Q: 'a + PartialEq>(
+        self.try_get_with_len(key)
+            .map(|found| found.value)
+            .map_err(|_| None)
+    }
+    /// Like [Trie::try_get], but also reports how much of 'key' was matched.
+    pub fn try_get_with_len<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Result<Match<&V>, Option<Match<&V>>>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        match self.try_get_handle(key) {
+            Ok(found) | Err(Some(found)) => Ok(found.map(|leaf| leaf.get(&self.leaves).get())),
+            Err(None) => Err(None),
+        }
+    }
+    pub fn try_get_mut<'a, Q: 'a + ?Sized + PartialEq>(
         &mut self,
         key: impl IntoIterator<Item = &'a Q>,
     ) -> Result<&mut V, Option<&mut V>>
@@ -181,12 +289,40 @@ impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
         B: Branch<K, V, Q>,
     {
         match self.try_get_handle(key) {
-            Ok(node) => Ok(node.get_mut(&mut self.leaves).get_mut()),
-            Err(Some(node)) => Err(Some(node.get_mut(&mut self.leaves).get_mut())),
+            Ok(found) | Err(Some(found)) => Ok(found.value.get_mut(&mut self.leaves).get_mut()),
             Err(None) => Err(None),
         }
     }
-    pub fn get_deepest<'a, Q: 'a + PartialEq>(
+    /// The value of 'key' itself inside [Result::Ok],
+    /// otherwise the one of the longest key 'key' starts with, if any.
+    pub fn try_get_exact<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Result<&V, Option<&V>>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let get = |found: Match<LeafHandle<V>>| found.value.get(&self.leaves).get();
+        self.try_get_handle(key)
+            .map(get)
+            .map_err(Option::remap(get))
+    }
+    pub fn try_get_exact_mut<'a, Q: 'a + ?Sized + PartialEq>(
+        &mut self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Result<&mut V, Option<&mut V>>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        match self.try_get_handle(key) {
+            Ok(found) => Ok(found.value.get_mut(&mut self.leaves).get_mut()),
+            Err(Some(found)) => Err(Some(found.value.get_mut(&mut self.leaves).get_mut())),
+            Err(None) => Err(None),
+        }
+    }
+    pub fn get_deepest<'a, Q: 'a + ?Sized + PartialEq>(
         &self,
         key: impl IntoIterator<Item = &'a Q>,
     ) -> Option<&V>
@@ -196,7 +332,7 @@ impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
     {
         self.try_get(key).map_or_else(identity, Option::Some)
     }
-    pub fn get_deepest_mut<'a, Q: 'a + PartialEq>(
+    pub fn get_deepest_mut<'a, Q: 'a + ?Sized + PartialEq>(
         &mut self,
         key: impl IntoIterator<Item = &'a Q>,
     ) -> Option<&mut V>
@@ -206,7 +342,70 @@ impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
     {
         self.try_get_mut(key).map_or_else(identity, Option::Some)
     }
-    pub fn remove<'a, Q: 'a + PartialEq>(
+    /// Like [Trie::get_deepest], but also reports how much of 'key' was matched.
+    pub fn get_deepest_with_len<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Option<Match<&V>>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        self.try_get_with_len(key)
+            .map_or_else(identity, Option::Some)
+    }
+    pub fn get_prefixes<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Vec<&V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let mut ret = vec![];
+        let _ = VNode::start(self.root.leak()).find(self, key, |found, _| {
+            ret.extend(found.value.leaf(self));
+            Err::<(), _>(None)
+        });
+        ret
+    }
+    /// Repeatedly takes the longest non-empty key that 'input' continues with,
+    /// yielding the number of elements it consumed together with its value,
+    /// or [Result::Err] with the single element skipped when there is none.
+    pub fn tokenize<'a, 'i, Q: PartialEq>(
+        &'a self,
+        input: &'i [Q],
+    ) -> impl use<'a, 'i, K, V, B, S, Q> + Iterator<Item = Result<(usize, &'a V), &'i Q>>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let mut rest = input;
+        iter::from_fn(move || {
+            let first = rest.first()?;
+            let token = self
+                .get_deepest_with_len(rest)
+                .filter(|found| found.depth > 0)
+                .map(|found| (found.depth, found.value))
+                .ok_or(first);
+            rest = &rest[token.map_or(1, |(len, _)| len)..];
+            Some(token)
+        })
+    }
+    pub fn subtrie<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        prefix: impl IntoIterator<Item = &'a Q>,
+    ) -> Option<SubTrie<'_, K, V, B, S>>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let vnode = VNode::start(self.root.leak())
+            .descend(self, prefix, |_, _, _| true)
+            .ok()?;
+        Some(SubTrie::new(self, vnode))
+    }
+    pub fn remove<'a, Q: 'a + ?Sized + PartialEq>(
         &mut self,
         key: impl IntoIterator<Item = &'a Q>,
     ) -> Option<V>
@@ -237,7 +436,7 @@ impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
                 #[cfg(feature = "testing")]
                 _this,
                 Handle::new_null(),
-                vec![],
+                Prefix::new(),
                 (),
             )
         });
@@ -254,10 +453,22 @@ impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut V> {
         VNode::start(self.root.leak()).iter_mut(self)
     }
+    pub fn iter_with_keys(&self) -> impl Iterator<Item = (Vec<K>, &V)>
+    where
+        K: Clone,
+    {
+        VNode::start(self.root.leak()).iter_keyed(self)
+    }
+    pub fn keys(&self) -> impl Iterator<Item = Vec<K>>
+    where
+        K: Clone,
+    {
+        self.iter_with_keys().map(|(k, _)| k)
+    }
 }
 
-impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
-    fn get_handle<'a, Q: 'a + PartialEq>(
+impl<K, V, B: Branch<K, V>, S: Storage> Trie<K, V, B, S> {
+    fn get_handle<'a, Q: 'a + ?Sized + PartialEq>(
         &self,
         key: impl IntoIterator<Item = &'a Q>,
     ) -> Option<LeafHandle<V>>
@@ -270,17 +481,24 @@ impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
             .ok()?
             .leaf_handle(self)
     }
-    fn try_get_handle<'a, Q: 'a + PartialEq>(
+    fn try_get_handle<'a, Q: 'a + ?Sized + PartialEq>(
         &self,
         key: impl IntoIterator<Item = &'a Q>,
-    ) -> Result<LeafHandle<V>, Option<LeafHandle<V>>>
+    ) -> Result<Match<LeafHandle<V>>, Option<Match<LeafHandle<V>>>>
     where
         K: Borrow<Q>,
         B: Branch<K, V, Q>,
     {
-        VNode::start(self.root.leak())
-            .find(self, key, |node, this| Err(node.leaf_handle(this)))
-            .map_err(|node| node.leaf_handle(self))
+        match VNode::start(self.root.leak()).find(self, key, |found, this| {
+            Err(found
+                .value
+                .leaf_handle(this)
+                .map(|leaf| found.map(|_| leaf)))
+        }) {
+            Ok(found) if found.exact => Ok(found),
+            Ok(deepest) => Err(Some(deepest)),
+            Err(_) => Err(None),
+        }
     }
 }
 
@@ -0,0 +1,152 @@
+use crate::trie::{
+    Trie,
+    branch::{BTreeBranch, Branch},
+    vnode::VNode,
+};
+use std::{borrow::Borrow, fmt::Debug};
+
+/// [Trie] mapping each key to any number of values, kept in insertion order.
+///
+/// Keys disappear together with their last value, so no empty leaves are left behind.
+pub struct TrieMultiMap<K, V, B = BTreeBranch<K, Vec<V>>> {
+    trie: Trie<K, Vec<V>, B>,
+    len: usize,
+}
+impl<K, V, B> Default for TrieMultiMap<K, V, B> {
+    fn default() -> Self {
+        Self {
+            trie: Default::default(),
+            len: 0,
+        }
+    }
+}
+impl<K: Debug, V: Debug, B> Debug for TrieMultiMap<K, V, B>
+where
+    Trie<K, Vec<V>, B>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TrieMultiMap").field(&self.trie).finish()
+    }
+}
+impl<K: PartialEq, V, B: Branch<K, Vec<V>>, I: IntoIterator<Item = K>> FromIterator<(I, V)>
+    for TrieMultiMap<K, V, B>
+{
+    fn from_iter<T: IntoIterator<Item = (I, V)>>(iter: T) -> Self {
+        let mut this = Self::default();
+        for (key, value) in iter {
+            this.insert(key, value);
+        }
+        this
+    }
+}
+impl<K, V, B: Branch<K, Vec<V>>> TrieMultiMap<K, V, B> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Number of values across all keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Number of distinct keys.
+    pub fn keys_len(&self) -> usize {
+        self.trie.len()
+    }
+    /// Appends 'value' to those already stored for 'key'.
+    pub fn insert(&mut self, key: impl IntoIterator<Item = K>, value: V)
+    where
+        K: PartialEq,
+    {
+        let node = VNode::start(self.trie.root.leak()).make_descend(&mut self.trie, key);
+        match node.leaf_mut(&mut self.trie) {
+            Some(values) => values.push(value),
+            None => _ = node.make_leaf(&mut self.trie, vec![value]),
+        }
+        self.len += 1;
+    }
+    pub fn get_all<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> impl Iterator<Item = &V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, Vec<V>, Q>,
+    {
+        self.trie.get(key).into_iter().flatten()
+    }
+    pub fn contains_key<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> bool
+    where
+        K: Borrow<Q>,
+        B: Branch<K, Vec<V>, Q>,
+    {
+        self.trie.get(key).is_some()
+    }
+    /// Removes the first value stored for 'key' that is equal to 'value',
+    /// together with 'key' itself if it was the last one.
+    pub fn remove_one<'a, Q: 'a + ?Sized + PartialEq>(
+        &mut self,
+        key: impl IntoIterator<Item = &'a Q>,
+        value: &V,
+    ) -> Option<V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, Vec<V>, Q>,
+        V: PartialEq,
+    {
+        let ret = VNode::start(self.trie.root.leak())
+            .dive(
+                &mut self.trie,
+                key,
+                |_, _, _| true,
+                |node, trie| {
+                    let values = node.leaf_mut(trie)?;
+                    let ret = values.remove(values.iter().position(|v| v == value)?);
+                    if values.is_empty() {
+                        let (node, _) = node.take_leaf(trie)?;
+                        node.prune_branch(trie);
+                    }
+                    Some(ret)
+                },
+                |node, trie, _| node.prune_branch(trie),
+            )
+            .ok()?;
+        self.len -= 1;
+        Some(ret)
+    }
+    /// Removes 'key' with all of its values.
+    pub fn remove_all<'a, Q: 'a + ?Sized + PartialEq>(
+        &mut self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Vec<V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, Vec<V>, Q>,
+    {
+        let ret = self.trie.remove(key).unwrap_or_default();
+        self.len -= ret.len();
+        ret
+    }
+    pub fn clear(&mut self) {
+        self.trie.clear();
+        self.len = 0;
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &V> {
+        self.trie.iter().flatten()
+    }
+    pub fn iter_with_keys(&self) -> impl Iterator<Item = (Vec<K>, &[V])>
+    where
+        K: Clone,
+    {
+        self.trie
+            .iter_with_keys()
+            .map(|(key, values)| (key, values.as_slice()))
+    }
+    pub fn as_trie(&self) -> &Trie<K, Vec<V>, B> {
+        &self.trie
+    }
+}
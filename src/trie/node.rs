@@ -1,5 +1,9 @@
 use crate::{
-    trie::{Handle, LeafHandle, NodeDebug, Trie, handle::Shared, leaf::Leaf},
+    trie::{
+        Handle, LeafHandle, NodeDebug, Trie,
+        leaf::Leaf,
+        storage::{Storage, Store},
+    },
     util::debug_fn,
 };
 use std::{fmt::Debug, mem::replace};
@@ -14,6 +18,11 @@ pub enum DataHandle<V, B> {
         leaf: LeafHandle<V>,
         branch: Handle<B>,
     },
+    /// Like [DataHandle::Leaf], but only marking its key as present without any value,
+    /// see [TrieSet](crate::TrieSet).
+    Marked,
+    /// Like [DataHandle::Full], but marked instead of holding a leaf.
+    MarkedBranch(Handle<B>),
 }
 impl<V, B> Debug for DataHandle<V, B> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -26,9 +35,16 @@ impl<V, B> Debug for DataHandle<V, B> {
                 .field("leaf", leaf)
                 .field("branch", branch)
                 .finish(),
+            Self::Marked => write!(f, "Marked"),
+            Self::MarkedBranch(arg0) => f.debug_tuple("MarkedBranch").field(arg0).finish(),
         }
     }
 }
+impl<V, B> Clone for DataHandle<V, B> {
+    fn clone(&self) -> Self {
+        self.leak()
+    }
+}
 impl<V, B> From<()> for DataHandle<V, B> {
     fn from((): ()) -> Self {
         Self::Empty
@@ -39,6 +55,16 @@ impl<V, B> From<(LeafHandle<V>, Handle<B>)> for DataHandle<V, B> {
         Self::Full { leaf, branch }
     }
 }
+impl<V, B> From<(Option<LeafHandle<V>>, Option<Handle<B>>)> for DataHandle<V, B> {
+    fn from(leaf_branch: (Option<LeafHandle<V>>, Option<Handle<B>>)) -> Self {
+        match leaf_branch {
+            (None, None) => Self::Empty,
+            (Some(leaf), None) => Self::Leaf(leaf),
+            (None, Some(branch)) => Self::Branch(branch),
+            (Some(leaf), Some(branch)) => Self::Full { leaf, branch },
+        }
+    }
+}
 impl<V, B> DataHandle<V, B> {
     pub fn leak(&self) -> Self {
         match self {
@@ -49,49 +75,100 @@ impl<V, B> DataHandle<V, B> {
                 leaf: leaf.leak(),
                 branch: branch.leak().into(),
             },
+            DataHandle::Marked => DataHandle::Marked,
+            DataHandle::MarkedBranch(handle) => DataHandle::MarkedBranch(handle.leak()),
         }
     }
     pub fn leaf(&self) -> Option<LeafHandle<V>> {
         match self.leak() {
-            DataHandle::Empty | DataHandle::Branch(_) => None,
+            DataHandle::Empty
+            | DataHandle::Branch(_)
+            | DataHandle::Marked
+            | DataHandle::MarkedBranch(_) => None,
             DataHandle::Leaf(leaf) | DataHandle::Full { leaf, .. } => Some(leaf),
         }
     }
     pub fn branch(&self) -> Option<Handle<B>> {
         match self.leak() {
-            DataHandle::Empty | DataHandle::Leaf(_) => None,
-            DataHandle::Branch(branch) | DataHandle::Full { branch, .. } => Some(branch.into()),
+            DataHandle::Empty | DataHandle::Leaf(_) | DataHandle::Marked => None,
+            DataHandle::Branch(branch)
+            | DataHandle::Full { branch, .. }
+            | DataHandle::MarkedBranch(branch) => Some(branch),
+        }
+    }
+    pub fn is_marked(&self) -> bool {
+        matches!(self, DataHandle::Marked | DataHandle::MarkedBranch(_))
+    }
+    /// Marks 'self' if 'marked', which it must not have a leaf for.
+    pub fn with_mark(self, marked: bool) -> Self {
+        match (marked, self) {
+            (true, DataHandle::Empty) => DataHandle::Marked,
+            (true, DataHandle::Branch(branch)) => DataHandle::MarkedBranch(branch),
+            (true, DataHandle::Leaf(_) | DataHandle::Full { .. }) => {
+                unreachable!("marking a node that has a leaf")
+            }
+            (_, data) => data,
+        }
+    }
+    pub(crate) fn cast<W, B2>(self) -> DataHandle<W, B2> {
+        match self {
+            DataHandle::Empty => DataHandle::Empty,
+            DataHandle::Leaf(leaf) => DataHandle::Leaf(leaf.cast()),
+            DataHandle::Branch(branch) => DataHandle::Branch(branch.cast()),
+            DataHandle::Full { leaf, branch } => DataHandle::Full {
+                leaf: leaf.cast(),
+                branch: branch.cast(),
+            },
+            DataHandle::Marked => DataHandle::Marked,
+            DataHandle::MarkedBranch(branch) => DataHandle::MarkedBranch(branch.cast()),
         }
     }
     pub fn leaf_branch(&self) -> (Option<LeafHandle<V>>, Option<Handle<B>>) {
         match self.leak() {
-            DataHandle::Empty => (None, None),
+            DataHandle::Empty | DataHandle::Marked => (None, None),
             DataHandle::Leaf(leaf) => (Some(leaf), None),
-            DataHandle::Branch(branch) => (None, Some(branch.into())),
-            DataHandle::Full { leaf, branch } => (Some(leaf), Some(branch.into())),
+            DataHandle::Branch(branch) | DataHandle::MarkedBranch(branch) => (None, Some(branch)),
+            DataHandle::Full { leaf, branch } => (Some(leaf), Some(branch)),
         }
     }
 }
 
+/// Prefix of a [Node], kept inline while it fits into 16 bytes with the 'smallvec' feature.
+#[cfg(not(feature = "smallvec"))]
+pub type Prefix<K> = Vec<K>;
+#[cfg(feature = "smallvec")]
+pub type Prefix<K> = super::prefix::InlineVec<K>;
+
 #[derive(Debug)]
 pub struct Node<K, V, B> {
     previous: NodeHandle<K, V, B>,
-    prefix: Vec<K>,
+    prefix: Prefix<K>,
     data: DataHandle<V, B>,
     #[cfg(feature = "testing")]
     this: NodeHandle<K, V, B>,
 }
+impl<K: Clone, V, B> Clone for Node<K, V, B> {
+    fn clone(&self) -> Self {
+        Self {
+            previous: self.previous.leak(),
+            prefix: self.prefix.clone(),
+            data: self.data.leak(),
+            #[cfg(feature = "testing")]
+            this: self.this.leak(),
+        }
+    }
+}
 impl<K, V, B: NodeDebug<K, V, B>> NodeDebug<K, V, B> for Node<K, V, B> {
     fn default_with_owner(#[cfg(feature = "testing")] owner: NodeHandle<K, V, B>) -> Self {
         Self::from(
             #[cfg(feature = "testing")]
             owner,
             Handle::new_null(),
-            Vec::default(),
+            Prefix::default(),
             (),
         )
     }
-    fn debug<'a>(&'a self, trie: &'a Trie<K, V, B>) -> impl 'a + Debug
+    fn debug<'a, S: Storage>(&'a self, trie: &'a Trie<K, V, B, S>) -> impl 'a + Debug
     where
         K: Debug,
         V: Debug,
@@ -102,12 +179,14 @@ impl<K, V, B: NodeDebug<K, V, B>> NodeDebug<K, V, B> for Node<K, V, B> {
                 DataHandle::Leaf(_) => "Node::Leaf",
                 DataHandle::Branch(_) => "Node::Branch",
                 DataHandle::Full { .. } => "Node::Full",
+                DataHandle::Marked => "Node::Marked",
+                DataHandle::MarkedBranch(_) => "Node::MarkedBranch",
             });
             if !self.prefix().is_empty() {
                 f.field("prefix", &self.prefix);
             }
-            if let Some(leaf) = self.leaf() {
-                f.field("leaf", &leaf.get(&trie.leaves));
+            if let Some(leaf) = self._get_leaf(&trie.leaves) {
+                f.field("leaf", leaf);
             }
             if let Some(branch) = self.branch() {
                 f.field("branch", &branch.get(&trie.branches).debug(trie));
@@ -122,7 +201,16 @@ impl<K, V, B: NodeDebug<K, V, B>> NodeDebug<K, V, B> for Node<K, V, B> {
     }
 }
 impl<K, V, B> Node<K, V, B> {
-    pub fn _from_null<T: Into<DataHandle<V, B>>>(prefix: Vec<K>, handle: T) -> Self {
+    pub(crate) fn cast<W, B2>(self) -> Node<K, W, B2> {
+        Node {
+            previous: self.previous.cast(),
+            prefix: self.prefix,
+            data: self.data.cast(),
+            #[cfg(feature = "testing")]
+            this: self.this.cast(),
+        }
+    }
+    pub fn _from_null<T: Into<DataHandle<V, B>>>(prefix: Prefix<K>, handle: T) -> Self {
         Self::from(
             #[cfg(feature = "testing")]
             Handle::new_null(),
@@ -134,7 +222,7 @@ impl<K, V, B> Node<K, V, B> {
     pub fn from<T: Into<DataHandle<V, B>>>(
         #[cfg(feature = "testing")] this: NodeHandle<K, V, B>,
         previous: NodeHandle<K, V, B>,
-        prefix: Vec<K>,
+        prefix: Prefix<K>,
         handle: T,
     ) -> Self {
         Self {
@@ -151,10 +239,18 @@ impl<K, V, B> Node<K, V, B> {
             _ => false,
         }
     }
-    pub fn prefix(&self) -> &Vec<K> {
+    pub fn prefix(&self) -> &[K] {
         &self.prefix
     }
-    pub(super) fn prefix_mut(&mut self) -> &mut Vec<K> {
+    /// Bytes the prefix occupies on the heap, none while it is stored inline.
+    pub(super) fn prefix_heap_size(&self) -> usize {
+        #[cfg(feature = "smallvec")]
+        if !self.prefix.spilled() {
+            return 0;
+        }
+        self.prefix.capacity() * size_of::<K>()
+    }
+    pub(super) fn prefix_mut(&mut self) -> &mut Prefix<K> {
         &mut self.prefix
     }
     pub(super) fn previous(&self) -> NodeHandle<K, V, B> {
@@ -164,8 +260,8 @@ impl<K, V, B> Node<K, V, B> {
     pub(super) fn set_this(
         &mut self,
         this: NodeHandle<K, V, B>,
-        branches: &mut Shared<B>,
-        leaves: &mut Shared<Leaf<V>>,
+        branches: &mut impl Store<B>,
+        leaves: &mut impl Store<Leaf<V>>,
     ) -> NodeHandle<K, V, B>
     where
         B: NodeDebug<K, V, B>,
@@ -190,25 +286,47 @@ impl<K, V, B> Node<K, V, B> {
         }
         old_a
     }
+    pub(super) fn replace_data(&mut self, data: DataHandle<V, B>) -> DataHandle<V, B> {
+        replace(&mut self.data, data)
+    }
+    /// Leaves 'self' empty, returning its prefix starting at 'at' together with its data.
+    pub(super) fn take_data(&mut self, at: usize) -> (Prefix<K>, DataHandle<V, B>) {
+        let prefix = self.prefix.split_off(at);
+        self.prefix.clear();
+        (prefix, replace(&mut self.data, DataHandle::Empty))
+    }
+    pub(super) fn into_parts(self) -> (Prefix<K>, DataHandle<V, B>) {
+        (self.prefix, self.data)
+    }
     pub(super) fn set_previous(&mut self, previous: NodeHandle<K, V, B>) -> NodeHandle<K, V, B> {
         replace(&mut self.previous, previous)
     }
     pub fn branch(&self) -> Option<Handle<B>> {
         self.data.branch()
     }
-    pub fn get_branch<'a>(&self, branches: &'a Shared<B>) -> Option<&'a B> {
+    pub fn get_branch<'a>(&self, branches: &'a impl Store<B>) -> Option<&'a B> {
         Some(self.data.branch()?.get(branches))
     }
-    pub fn get_branch_mut<'a>(&self, branches: &'a mut Shared<B>) -> Option<&'a mut B> {
+    pub fn get_branch_mut<'a>(&self, branches: &'a mut impl Store<B>) -> Option<&'a mut B> {
         Some(self.data.branch()?.get_mut(branches))
     }
     pub fn leaf(&self) -> Option<LeafHandle<V>> {
         self.data.leaf()
     }
-    pub fn _get_leaf<'a>(&self, leaves: &'a Shared<Leaf<V>>) -> Option<&'a Leaf<V>> {
+    pub fn is_marked(&self) -> bool {
+        self.data.is_marked()
+    }
+    /// Whether the key leading to 'self' is present, either with a leaf or marked.
+    pub fn has_key(&self) -> bool {
+        self.is_marked() || self.leaf().is_some()
+    }
+    pub fn _get_leaf<'a>(&self, leaves: &'a impl Store<Leaf<V>>) -> Option<&'a Leaf<V>> {
         Some(self.data.leaf()?.get(leaves))
     }
-    pub fn _get_leaf_mut<'a>(&self, leaves: &'a mut Shared<Leaf<V>>) -> Option<&'a mut Leaf<V>> {
+    pub fn _get_leaf_mut<'a>(
+        &self,
+        leaves: &'a mut impl Store<Leaf<V>>,
+    ) -> Option<&'a mut Leaf<V>> {
         Some(self.data.leaf()?.get_mut(leaves))
     }
     pub fn leaf_branch(&self) -> (Option<LeafHandle<V>>, Option<Handle<B>>) {
@@ -216,8 +334,8 @@ impl<K, V, B> Node<K, V, B> {
     }
     pub fn _get_leaf_branch<'a, 'b>(
         &self,
-        leaves: &'a Shared<Leaf<V>>,
-        branches: &'b Shared<B>,
+        leaves: &'a impl Store<Leaf<V>>,
+        branches: &'b impl Store<B>,
     ) -> (Option<&'a V>, Option<&'b B>) {
         let (leaf, branch) = self.leaf_branch();
         (
@@ -227,8 +345,8 @@ impl<K, V, B> Node<K, V, B> {
     }
     pub fn _get_leaf_branch_mut<'a, 'b>(
         &self,
-        leaves: &'a mut Shared<Leaf<V>>,
-        branches: &'b mut Shared<B>,
+        leaves: &'a mut impl Store<Leaf<V>>,
+        branches: &'b mut impl Store<B>,
     ) -> (Option<&'a mut V>, Option<&'b mut B>) {
         let (leaf, branch) = self.leaf_branch();
         (
@@ -241,60 +359,55 @@ impl<K, V, B> Node<K, V, B> {
     pub fn make_leaf(
         &mut self,
         #[cfg(feature = "testing")] this: NodeHandle<K, V, B>,
-        leaves: &mut Shared<Leaf<V>>,
+        leaves: &mut impl Store<Leaf<V>>,
         value: V,
     ) -> Option<V> {
+        let mut value = Some(value);
+        let leaf = self.make_leaf_with(|| {
+            Handle::new(
+                leaves,
+                Leaf::new(
+                    #[cfg(feature = "testing")]
+                    this,
+                    value.take().unwrap(),
+                ),
+            )
+        });
+        let leaf = leaf.err()?;
+        Some(leaf.get_mut(leaves).replace(value.unwrap()))
+    }
+    /// Attaches the leaf created by 'new', unless there already is one, which is returned instead.
+    pub fn make_leaf_with(
+        &mut self,
+        new: impl FnOnce() -> LeafHandle<V>,
+    ) -> Result<(), LeafHandle<V>> {
         match self.data.leak() {
-            DataHandle::Empty => {
-                self.data = DataHandle::Leaf(Handle::new(
-                    leaves,
-                    Leaf::new(
-                        #[cfg(feature = "testing")]
-                        this,
-                        value,
-                    ),
-                ));
-                None
-            }
-            DataHandle::Leaf(leaf) | DataHandle::Full { leaf, .. } => {
-                Some(leaf.get_mut(leaves).replace(value))
+            DataHandle::Empty | DataHandle::Marked => {
+                self.data = DataHandle::Leaf(new());
+                Ok(())
             }
-            DataHandle::Branch(handle) => {
+            DataHandle::Leaf(leaf) | DataHandle::Full { leaf, .. } => Err(leaf),
+            DataHandle::Branch(branch) | DataHandle::MarkedBranch(branch) => {
                 self.data = DataHandle::Full {
-                    leaf: Handle::new(
-                        leaves,
-                        Leaf::new(
-                            #[cfg(feature = "testing")]
-                            this,
-                            value,
-                        ),
-                    ),
-                    branch: handle,
+                    leaf: new(),
+                    branch,
                 };
-                None
+                Ok(())
             }
         }
     }
     pub fn make_leaf_at(
         &mut self,
         this: NodeHandle<K, V, B>,
-        branches: &mut Shared<B>,
-        leaves: &mut Shared<Leaf<V>>,
+        branches: &mut impl Store<B>,
+        leaves: &mut impl Store<Leaf<V>>,
         value: V,
         leaf_at: usize,
-    ) -> (Option<V>, Option<(K, Node<K, V, B>)>)
+    ) -> (Option<V>, SplitOff<K, V, B>)
     where
         B: Default + NodeDebug<K, V, B>,
     {
-        assert!(leaf_at <= self.prefix.len());
-        #[cfg(feature = "testing")]
-        assert_eq!(self.this, this);
-        let node = (leaf_at < self.prefix.len()).then_some(()).and_then(|()| {
-            let node = self.make_branch_at(this.leak(), branches, leaf_at).1;
-            debug_assert_eq!(self.leaf(), None);
-            node
-        });
-        debug_assert_eq!(leaf_at, self.prefix.len());
+        let node = self.split_at(this.leak(), branches, leaf_at);
         (
             self.make_leaf(
                 #[cfg(feature = "testing")]
@@ -305,24 +418,73 @@ impl<K, V, B> Node<K, V, B> {
             node,
         )
     }
-    pub fn take_leaf(&mut self, leaves: &mut Shared<Leaf<V>>) -> Option<V> {
+    /// Marks the key leading to 'self' as present, returning whether it wasn't already.
+    pub fn make_mark(&mut self) -> bool {
+        match self.data.leak() {
+            DataHandle::Empty => self.data = DataHandle::Marked,
+            DataHandle::Branch(branch) => self.data = DataHandle::MarkedBranch(branch),
+            _ => return false,
+        }
+        true
+    }
+    /// Removes the mark placed by [Node::make_mark], returning whether there was one.
+    pub fn take_mark(&mut self) -> bool {
         match self.data.leak() {
-            DataHandle::Empty | DataHandle::Branch(_) => None,
+            DataHandle::Marked => {
+                self.data = DataHandle::Empty;
+                self.prefix.clear();
+            }
+            DataHandle::MarkedBranch(branch) => self.data = DataHandle::Branch(branch),
+            _ => return false,
+        }
+        true
+    }
+    /// Splits off everything past 'at' into a new [Node] behind a branch, see [Node::make_branch_at].
+    pub fn split_at(
+        &mut self,
+        this: NodeHandle<K, V, B>,
+        branches: &mut impl Store<B>,
+        leaf_at: usize,
+    ) -> SplitOff<K, V, B>
+    where
+        B: Default + NodeDebug<K, V, B>,
+    {
+        assert!(leaf_at <= self.prefix.len());
+        #[cfg(feature = "testing")]
+        assert_eq!(self.this, this);
+        let node = (leaf_at < self.prefix.len()).then_some(()).and_then(|()| {
+            let node = self.make_branch_at(this.leak(), branches, leaf_at).1;
+            debug_assert_eq!(self.leaf(), None);
+            node
+        });
+        debug_assert_eq!(leaf_at, self.prefix.len());
+        node
+    }
+    pub fn take_leaf(&mut self, leaves: &mut impl Store<Leaf<V>>) -> Option<V> {
+        Some(self.take_leaf_handle()?.remove(leaves).unwrap())
+    }
+    /// Detaches the leaf without removing it from its [Shared].
+    pub fn take_leaf_handle(&mut self) -> Option<LeafHandle<V>> {
+        match self.data.leak() {
+            DataHandle::Empty
+            | DataHandle::Branch(_)
+            | DataHandle::Marked
+            | DataHandle::MarkedBranch(_) => None,
             DataHandle::Leaf(leaf) => {
                 self.data = DataHandle::Empty;
                 self.prefix.clear();
-                Some(leaf.remove(leaves).unwrap())
+                Some(leaf)
             }
             DataHandle::Full { leaf, branch } => {
                 self.data = DataHandle::Branch(branch);
-                Some(leaf.remove(leaves).unwrap())
+                Some(leaf)
             }
         }
     }
     pub fn make_branch(
         &mut self,
         #[cfg(feature = "testing")] _this: NodeHandle<K, V, B>,
-        branches: &mut Shared<B>,
+        branches: &mut impl Store<B>,
     ) -> Handle<B>
     where
         B: Default + NodeDebug<K, V, B>,
@@ -342,6 +504,16 @@ impl<K, V, B> Node<K, V, B> {
                 .into();
                 DataHandle::Branch(branch_handle.leak())
             }
+            DataHandle::Marked => {
+                branch_handle = Handle::new(
+                    branches,
+                    B::default_with_owner(
+                        #[cfg(feature = "testing")]
+                        _this,
+                    ),
+                );
+                DataHandle::MarkedBranch(branch_handle.leak())
+            }
             DataHandle::Leaf(leaf) => {
                 branch_handle = Handle::new(
                     branches,
@@ -353,7 +525,9 @@ impl<K, V, B> Node<K, V, B> {
                 .into();
                 (leaf.leak(), branch_handle.leak()).into()
             }
-            handle @ DataHandle::Branch(branch) | handle @ DataHandle::Full { branch, .. } => {
+            handle @ DataHandle::Branch(branch)
+            | handle @ DataHandle::Full { branch, .. }
+            | handle @ DataHandle::MarkedBranch(branch) => {
                 branch_handle = branch.leak();
                 handle.leak()
             }
@@ -363,7 +537,7 @@ impl<K, V, B> Node<K, V, B> {
     pub fn make_branch_at(
         &mut self,
         this: NodeHandle<K, V, B>,
-        branches: &mut Shared<B>,
+        branches: &mut impl Store<B>,
         branch_at: usize,
     ) -> (Handle<B>, Option<(K, Node<K, V, B>)>)
     where
@@ -373,9 +547,8 @@ impl<K, V, B> Node<K, V, B> {
         #[cfg(feature = "testing")]
         assert_eq!(self.this, this);
         let node = (branch_at < self.prefix().len()).then(|| {
-            let mut drain = self.prefix.drain(branch_at..);
-            let key = drain.next().unwrap();
-            let prefix = drain.collect();
+            let prefix = self.prefix.split_off(branch_at + 1);
+            let key = self.prefix.pop().unwrap();
             let node = Node::from(
                 #[cfg(feature = "testing")]
                 Handle::new_null(),
@@ -397,12 +570,16 @@ impl<K, V, B> Node<K, V, B> {
     }
     pub fn take_branch(&mut self) -> Option<Handle<B>> {
         match self.data.leak() {
-            DataHandle::Empty | DataHandle::Leaf(_) => None,
+            DataHandle::Empty | DataHandle::Leaf(_) | DataHandle::Marked => None,
             DataHandle::Branch(branch) => {
                 self.data = ().into();
                 self.prefix.clear();
                 Some(branch)
             }
+            DataHandle::MarkedBranch(branch) => {
+                self.data = DataHandle::Marked;
+                Some(branch)
+            }
             DataHandle::Full { leaf, branch } => {
                 self.data = DataHandle::Leaf(leaf.into());
                 Some(branch)
@@ -411,3 +588,5 @@ impl<K, V, B> Node<K, V, B> {
     }
 }
 pub type NodeHandle<K, V, B> = Handle<Node<K, V, B>>;
+/// Remainder of a [Node] split off behind a new branch, together with its key inside it.
+pub type SplitOff<K, V, B> = Option<(K, Node<K, V, B>)>;
@@ -0,0 +1,304 @@
+use crate::trie::storage::Store;
+use std::{
+    mem::replace,
+    sync::{Arc, OnceLock},
+};
+
+const BITS: u32 = 5;
+/// Slots per page, as well as children per inner level.
+const PAGE: usize = 1 << BITS;
+const MASK: usize = PAGE - 1;
+
+enum Entry<T> {
+    /// Index of the next vacant slot.
+    Vacant(usize),
+    Occupied(Arc<T>),
+}
+struct Slot<T> {
+    entry: Entry<T>,
+    #[cfg(feature = "checked-handles")]
+    generation: u32,
+}
+impl<T> Clone for Slot<T> {
+    fn clone(&self) -> Self {
+        Self {
+            entry: match &self.entry {
+                Entry::Vacant(next) => Entry::Vacant(*next),
+                Entry::Occupied(val) => Entry::Occupied(val.clone()),
+            },
+            #[cfg(feature = "checked-handles")]
+            generation: self.generation,
+        }
+    }
+}
+
+/// Radix tree of pages, [PAGE] slots wide at every level.
+enum Tree<T> {
+    Inner(Vec<Arc<Tree<T>>>),
+    Page(Vec<Slot<T>>),
+}
+impl<T> Clone for Tree<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Inner(children) => Self::Inner(children.clone()),
+            Self::Page(slots) => Self::Page(slots.clone()),
+        }
+    }
+}
+
+/// [Store] whose clones share pages, and the entries on them, until written to.
+///
+/// Cloning is O(1), writing to an entry afterwards copies the O(log n) pages on its way,
+/// plus the entry itself while another clone still refers to it.
+pub struct Paged<T> {
+    root: Arc<Tree<T>>,
+    /// Levels of [Tree::Inner] above the pages.
+    height: u32,
+    /// Slots in use, vacant or not.
+    slots: usize,
+    len: usize,
+    /// Head of the list of vacant slots, 'slots' if there are none.
+    next: usize,
+    /// Set once the first clone shares entries, so that writing to them doesn't need 'T: Clone'.
+    clone: OnceLock<fn(&T) -> T>,
+}
+impl<T> Default for Paged<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Clone> Clone for Paged<T> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            height: self.height,
+            slots: self.slots,
+            len: self.len,
+            next: self.next,
+            clone: OnceLock::from(*self.clone.get_or_init(|| T::clone)),
+        }
+    }
+}
+impl<T> Paged<T> {
+    pub fn new() -> Self {
+        Self {
+            root: Arc::new(Tree::Page(Vec::new())),
+            height: 0,
+            slots: 0,
+            len: 0,
+            next: 0,
+            clone: OnceLock::new(),
+        }
+    }
+    /// Whether 'self' and 'other' share all of their entries.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.root, &other.root)
+    }
+    fn slot(&self, index: usize) -> &Slot<T> {
+        let mut tree = &*self.root;
+        let mut shift = self.height * BITS;
+        loop {
+            match tree {
+                Tree::Inner(children) => {
+                    tree = &children[(index >> shift) & MASK];
+                    shift -= BITS;
+                }
+                Tree::Page(slots) => return &slots[index & MASK],
+            }
+        }
+    }
+    /// Like [Paged::slot], copying every page on the way that is still shared.
+    fn slot_mut(&mut self, index: usize) -> &mut Slot<T> {
+        let mut tree = Arc::make_mut(&mut self.root);
+        let mut shift = self.height * BITS;
+        loop {
+            match tree {
+                Tree::Inner(children) => {
+                    tree = Arc::make_mut(&mut children[(index >> shift) & MASK]);
+                    shift -= BITS;
+                }
+                Tree::Page(slots) => return &mut slots[index & MASK],
+            }
+        }
+    }
+    fn push(&mut self, slot: Slot<T>) {
+        let index = self.slots;
+        if index == PAGE << (self.height * BITS) {
+            let root = replace(&mut self.root, Arc::new(Tree::Inner(Vec::new())));
+            Arc::make_mut(&mut self.root).push(root);
+            self.height += 1;
+        }
+        let mut tree = Arc::make_mut(&mut self.root);
+        let mut shift = self.height * BITS;
+        loop {
+            match tree {
+                Tree::Inner(children) => {
+                    let digit = (index >> shift) & MASK;
+                    if digit == children.len() {
+                        children.push(Arc::new(match shift {
+                            BITS => Tree::Page(Vec::with_capacity(PAGE)),
+                            _ => Tree::Inner(Vec::with_capacity(PAGE)),
+                        }));
+                    }
+                    tree = Arc::make_mut(&mut children[digit]);
+                    shift -= BITS;
+                }
+                Tree::Page(slots) => break slots.push(slot),
+            }
+        }
+        self.slots += 1;
+    }
+    /// The entry of 'val' to hand out by value, which has to copy it while it is still shared.
+    fn unshare(&self, val: Arc<T>) -> T {
+        Arc::try_unwrap(val).unwrap_or_else(|val| self.copy()(&val))
+    }
+    fn copy(&self) -> fn(&T) -> T {
+        *self
+            .clone
+            .get()
+            .expect("entries are only ever shared by cloning")
+    }
+}
+impl<T> Tree<T> {
+    fn push(&mut self, child: Arc<Self>) {
+        match self {
+            Self::Inner(children) => children.push(child),
+            Self::Page(_) => unreachable!(),
+        }
+    }
+}
+impl<T> Store<T> for Paged<T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn vacant_key(&self) -> usize {
+        self.next
+    }
+    fn insert(&mut self, val: T) -> usize {
+        let index = self.next;
+        let entry = Entry::Occupied(Arc::new(val));
+        if index == self.slots {
+            self.push(Slot {
+                entry,
+                #[cfg(feature = "checked-handles")]
+                generation: 0,
+            });
+            self.next = self.slots;
+        } else {
+            match replace(&mut self.slot_mut(index).entry, entry) {
+                Entry::Vacant(next) => self.next = next,
+                Entry::Occupied(_) => unreachable!(),
+            }
+        }
+        self.len += 1;
+        index
+    }
+    fn remove(&mut self, index: usize) -> T {
+        let next = self.next;
+        let slot = self.slot_mut(index);
+        let Entry::Occupied(_) = slot.entry else {
+            panic!("invalid key");
+        };
+        let Entry::Occupied(val) = replace(&mut slot.entry, Entry::Vacant(next)) else {
+            unreachable!()
+        };
+        #[cfg(feature = "checked-handles")]
+        {
+            slot.generation = slot.generation.wrapping_add(1);
+        }
+        self.next = index;
+        self.len -= 1;
+        self.unshare(val)
+    }
+    fn clear(&mut self) {
+        // keeps the slots around, so that their generations stay as well
+        for index in 0..self.slots {
+            let slot = self.slot_mut(index);
+            #[cfg(feature = "checked-handles")]
+            if let Entry::Occupied(_) = slot.entry {
+                slot.generation = slot.generation.wrapping_add(1);
+            }
+            slot.entry = Entry::Vacant(index + 1);
+        }
+        self.next = 0;
+        self.len = 0;
+    }
+    fn get(&self, index: usize) -> &T {
+        match &self.slot(index).entry {
+            Entry::Occupied(val) => val,
+            Entry::Vacant(_) => panic!("invalid key"),
+        }
+    }
+    fn get_mut(&mut self, index: usize) -> &mut T {
+        let copy = self.clone.get().copied();
+        let Entry::Occupied(val) = &mut self.slot_mut(index).entry else {
+            panic!("invalid key");
+        };
+        if Arc::get_mut(val).is_none() {
+            let copy = copy.expect("entries are only ever shared by cloning");
+            *val = Arc::new(copy(val));
+        }
+        Arc::get_mut(val).unwrap()
+    }
+    #[cfg(feature = "checked-handles")]
+    fn generation(&self, index: usize) -> u32 {
+        if index < self.slots {
+            self.slot(index).generation
+        } else {
+            0
+        }
+    }
+    #[cfg(feature = "checked-handles")]
+    fn check(&self, index: usize, generation: u32) {
+        let occupied = index < self.slots && matches!(self.slot(index).entry, Entry::Occupied(_));
+        super::slots::check::<T>(index, generation, self.generation(index), occupied);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_until_written() {
+        let mut paged = Paged::new();
+        let indices = Vec::from_iter((0..1000).map(|i| paged.insert(vec![i])));
+        let snapshot = paged.clone();
+        assert!(paged.ptr_eq(&snapshot));
+        paged.get_mut(indices[10]).push(0);
+        assert_eq!(paged.remove(indices[500]), [500]);
+        assert_eq!(paged.insert(vec![]), indices[500]);
+        assert!(!paged.ptr_eq(&snapshot));
+        for (i, &index) in indices.iter().enumerate() {
+            assert_eq!(snapshot.get(index), &[i]);
+        }
+        assert_eq!(paged.get(indices[10]), &[10, 0]);
+        assert!(paged.get(indices[500]).is_empty());
+        assert_eq!(paged.len(), 1000);
+        // untouched entries are still shared
+        let (Entry::Occupied(a), Entry::Occupied(b)) = (
+            &paged.slot(indices[11]).entry,
+            &snapshot.slot(indices[11]).entry,
+        ) else {
+            unreachable!()
+        };
+        assert!(Arc::ptr_eq(a, b));
+    }
+
+    #[test]
+    fn reuses_vacated() {
+        let mut paged = Paged::new();
+        for i in 0..100 {
+            paged.insert(i);
+        }
+        paged.remove(3);
+        paged.remove(70);
+        assert_eq!((paged.vacant_key(), paged.insert(0)), (70, 70));
+        assert_eq!(paged.insert(0), 3);
+        assert_eq!(paged.insert(0), 100);
+        paged.clear();
+        assert!(paged.is_empty());
+        assert_eq!(paged.insert(1), 0);
+        assert_eq!(paged.insert(2), 1);
+    }
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "rayon")]
+
+use crate::trie::{Leaf, Trie, branch::Branch, vnode::VNode};
+use rayon::prelude::*;
+use std::{collections::BTreeMap, iter};
+
+/// Nodes split at, whose leaves are part of none of the subtrees, followed by the subtrees.
+type Split<K, V, B> = (Vec<VNode<K, V, B>>, Vec<VNode<K, V, B>>);
+
+impl<K: Sync, V: Sync, B: Branch<K, V> + Sync> Trie<K, V, B> {
+    /// Splits at branch boundaries until there are enough independent subtrees to keep all threads busy.
+    fn par_split(&self) -> Split<K, V, B> {
+        let target = rayon::current_num_threads() * 4;
+        let mut split = vec![];
+        let mut subtrees = vec![VNode::start(self.root.leak())];
+        while subtrees.len() < target {
+            let len = split.len();
+            let mut next = Vec::with_capacity(target);
+            for node in subtrees {
+                let node = node.skip_prefix(self);
+                match node.branch(self) {
+                    Some(branch) => {
+                        next.extend(Branch::<K, V>::values(branch).map(VNode::start));
+                        split.push(node);
+                    }
+                    None => next.push(node),
+                }
+            }
+            subtrees = next;
+            if split.len() == len {
+                break;
+            }
+        }
+        (split, subtrees)
+    }
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = &V> {
+        let (split, subtrees) = self.par_split();
+        split
+            .into_par_iter()
+            .filter_map(|node| node.leaf(self))
+            .chain(
+                subtrees
+                    .into_par_iter()
+                    .flat_map_iter(|node| node.iter(self)),
+            )
+    }
+}
+impl<K: Clone + Ord + Send, V: Send, B: Branch<K, V> + Send> Trie<K, V, B> {
+    /// Like [FromParallelIterator], but builds a separate [Trie] for every first key element
+    /// and moves them under the root afterwards, ending up just like sequential insertion would.
+    ///
+    /// That move happens on the calling thread and relinks each node, branch and leaf
+    /// of every partition into the stores of the result one by one, costing O(n) in entries.
+    pub fn par_from_iter<I: IntoIterator<Item = K> + Send>(
+        par_iter: impl IntoParallelIterator<Item = (I, V)>,
+    ) -> Self {
+        let empty = || (None, BTreeMap::<K, Vec<(Vec<K>, V)>>::new());
+        let (root, partitions) = par_iter
+            .into_par_iter()
+            .fold(empty, |(mut root, mut partitions), (key, value)| {
+                let mut key = key.into_iter();
+                match key.next() {
+                    None => root = Some(value),
+                    Some(first) => partitions
+                        .entry(first)
+                        .or_default()
+                        .push((Vec::from_iter(key), value)),
+                }
+                (root, partitions)
+            })
+            .reduce(empty, |(root, mut left), (right_root, right)| {
+                for (first, mut entries) in right {
+                    left.entry(first).or_default().append(&mut entries);
+                }
+                (right_root.or(root), left)
+            });
+        let partitions: Vec<(K, Self)> = partitions
+            .into_par_iter()
+            .map(|(first, entries)| (first, Self::from_iter(entries)))
+            .collect();
+        let mut trie = Self::default();
+        for (first, partition) in partitions {
+            trie.graft(iter::once(first), partition);
+        }
+        if let Some(value) = root {
+            trie.insert(iter::empty(), value);
+        }
+        trie
+    }
+}
+impl<K, V: Send, B> Trie<K, V, B> {
+    /// Unlike [Trie::par_iter], this hands out the leaves in the order they are stored in,
+    /// as splitting at branch boundaries would need mutable access to all of them at once.
+    pub fn par_iter_mut(&mut self) -> impl ParallelIterator<Item = &mut V> {
+        self.leaves.par_iter_mut().map(Leaf::get_mut)
+    }
+}
+impl<K, V, B> FromParallelIterator<(K, V)> for Trie<K::Item, V, B>
+where
+    K: IntoIterator<Item: Clone + PartialEq + Send> + Send,
+    V: Send,
+    B: Branch<K::Item, V> + Send,
+{
+    /// Builds a [Trie] per thread and merges them afterwards, later values replacing earlier ones.
+    fn from_par_iter<I: IntoParallelIterator<Item = (K, V)>>(par_iter: I) -> Self {
+        par_iter
+            .into_par_iter()
+            .fold(Self::default, |mut trie, (key, value)| {
+                trie.insert(key, value);
+                trie
+            })
+            .reduce(Self::default, |mut left, right| {
+                left.graft(iter::empty(), right);
+                left
+            })
+    }
+}
+impl<K, V, B> ParallelExtend<(K, V)> for Trie<K::Item, V, B>
+where
+    K: IntoIterator<Item: Clone + PartialEq + Send> + Send,
+    V: Send,
+    B: Branch<K::Item, V> + Send,
+{
+    /// Unless 'self' is empty, merging in the newly built entries happens on the calling thread.
+    fn par_extend<I: IntoParallelIterator<Item = (K, V)>>(&mut self, par_iter: I) {
+        let other: Self = par_iter.into_par_iter().collect();
+        self.graft(iter::empty(), other);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::trie::{
+    Trie,
+    branch::{BTreeBranch, Branch},
+    storage::Pages,
+};
+use std::{borrow::Borrow, fmt::Debug, ops::Deref};
+
+/// Immutable [Trie] where every update returns a new version,
+/// sharing all nodes, branches and leaves it doesn't write to with the previous one.
+///
+/// Cloning is O(1), updates copy the few entries they change along the path to 'key',
+/// see [CowTrie](super::CowTrie) for how those are shared.
+pub struct PersistentTrie<K, V, B = BTreeBranch<K, V>> {
+    trie: Trie<K, V, B, Pages>,
+}
+impl<K, V, B> Default for PersistentTrie<K, V, B> {
+    fn default() -> Self {
+        Self::from(Trie::default())
+    }
+}
+impl<K, V, B> From<Trie<K, V, B, Pages>> for PersistentTrie<K, V, B> {
+    fn from(trie: Trie<K, V, B, Pages>) -> Self {
+        Self { trie }
+    }
+}
+impl<K: Clone, V: Clone, B: Clone> Clone for PersistentTrie<K, V, B> {
+    fn clone(&self) -> Self {
+        Self {
+            trie: self.trie.clone(),
+        }
+    }
+}
+impl<K, V, B> Deref for PersistentTrie<K, V, B> {
+    type Target = Trie<K, V, B, Pages>;
+    fn deref(&self) -> &Self::Target {
+        &self.trie
+    }
+}
+impl<K: Clone + Debug, V: Debug, B: Branch<K, V>> Debug for PersistentTrie<K, V, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter_with_keys()).finish()
+    }
+}
+impl<K: Clone + PartialEq, V: PartialEq, B: Branch<K, V>> PartialEq for PersistentTrie<K, V, B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter_with_keys().eq(other.iter_with_keys())
+    }
+}
+impl<K: PartialEq, V, B: Branch<K, V>, I: IntoIterator<Item = K>> FromIterator<(I, V)>
+    for PersistentTrie<K, V, B>
+{
+    fn from_iter<T: IntoIterator<Item = (I, V)>>(iter: T) -> Self {
+        let mut trie = Trie::default();
+        for (key, value) in iter {
+            trie.insert(key, value);
+        }
+        Self::from(trie)
+    }
+}
+impl<K, V, B> PersistentTrie<K, V, B> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn into_trie(self) -> Trie<K, V, B, Pages> {
+        self.trie
+    }
+}
+impl<K: Clone, V: Clone, B: Clone + Branch<K, V>> PersistentTrie<K, V, B> {
+    /// New version with 'value' inserted at 'key', replacing any previous one.
+    pub fn insert(&self, key: impl IntoIterator<Item = K>, value: V) -> Self
+    where
+        K: PartialEq,
+    {
+        self.update(|trie| trie.insert(key, value)).0
+    }
+    /// New version without 'key', or just another handle to 'self' when it isn't contained.
+    pub fn remove<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Self
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let key = Vec::from_iter(key);
+        if self.trie.get(key.iter().copied()).is_none() {
+            return self.clone();
+        }
+        self.update(|trie| trie.remove(key)).0
+    }
+    /// New version with whatever changes 'f' makes, leaving 'self' as it is.
+    pub fn update<T>(&self, f: impl FnOnce(&mut Trie<K, V, B, Pages>) -> T) -> (Self, T) {
+        let mut trie = self.trie.clone();
+        let ret = f(&mut trie);
+        (Self { trie }, ret)
+    }
+}
@@ -0,0 +1,296 @@
+#![cfg(feature = "smallvec")]
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    mem::{ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+    slice, vec,
+};
+
+/// Bytes kept inline before spilling to the heap, taking up no more room than a [Vec] would.
+type Buffer = [MaybeUninit<u64>; 2];
+
+union Data<K> {
+    inline: Buffer,
+    heap: (NonNull<K>, usize),
+}
+impl<K> Clone for Data<K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<K> Copy for Data<K> {}
+
+/// [Vec] keeping as many elements inline as fit into 16 bytes,
+/// so that keys larger than that always live on the heap.
+pub struct InlineVec<K> {
+    /// Length while inline, capacity of the heap allocation once spilled.
+    capacity: usize,
+    data: Data<K>,
+    _own: PhantomData<K>,
+}
+unsafe impl<K: Send> Send for InlineVec<K> {}
+unsafe impl<K: Sync> Sync for InlineVec<K> {}
+
+impl<K> InlineVec<K> {
+    /// Elements fitting inline, none for zero-sized or overaligned ones.
+    const INLINE: usize = if size_of::<K>() == 0 || align_of::<K>() > align_of::<Buffer>() {
+        0
+    } else {
+        size_of::<Buffer>() / size_of::<K>()
+    };
+    pub fn new() -> Self {
+        Self {
+            capacity: 0,
+            data: Data {
+                inline: [MaybeUninit::uninit(); 2],
+            },
+            _own: PhantomData,
+        }
+    }
+    pub fn spilled(&self) -> bool {
+        self.capacity > Self::INLINE
+    }
+    pub fn capacity(&self) -> usize {
+        self.capacity.max(Self::INLINE)
+    }
+    fn inline_ptr(&self) -> *const K {
+        unsafe { self.data.inline.as_ptr().cast() }
+    }
+    fn inline_mut_ptr(&mut self) -> *mut K {
+        unsafe { self.data.inline.as_mut_ptr().cast() }
+    }
+    /// Moves all elements into a [Vec], leaving 'self' empty and inline.
+    fn take_vec(&mut self) -> Vec<K> {
+        let vec = if self.spilled() {
+            let (ptr, len) = unsafe { self.data.heap };
+            unsafe { Vec::from_raw_parts(ptr.as_ptr(), len, self.capacity) }
+        } else {
+            let mut vec = Vec::with_capacity(self.capacity);
+            unsafe {
+                ptr::copy_nonoverlapping(self.inline_ptr(), vec.as_mut_ptr(), self.capacity);
+                vec.set_len(self.capacity);
+            }
+            vec
+        };
+        self.capacity = 0;
+        vec
+    }
+    /// Moves the elements of 'vec' inline if they fit, or takes over its allocation otherwise.
+    fn from_vec(vec: Vec<K>) -> Self {
+        let mut vec = ManuallyDrop::new(vec);
+        let mut this = Self::new();
+        if vec.len() <= Self::INLINE {
+            unsafe {
+                ptr::copy_nonoverlapping(vec.as_ptr(), this.inline_mut_ptr(), vec.len());
+                this.capacity = vec.len();
+                vec.set_len(0);
+                ManuallyDrop::drop(&mut vec);
+            }
+        } else {
+            let ptr = NonNull::new(vec.as_mut_ptr()).unwrap();
+            this.data = Data {
+                heap: (ptr, vec.len()),
+            };
+            this.capacity = vec.capacity();
+        }
+        this
+    }
+    fn with_vec<T>(&mut self, f: impl FnOnce(&mut Vec<K>) -> T) -> T {
+        let mut vec = self.take_vec();
+        let ret = f(&mut vec);
+        *self = Self::from_vec(vec);
+        ret
+    }
+    pub fn push(&mut self, val: K) {
+        if self.capacity < Self::INLINE {
+            unsafe { self.inline_mut_ptr().add(self.capacity).write(val) };
+            self.capacity += 1;
+        } else {
+            self.with_vec(|vec| vec.push(val));
+        }
+    }
+    pub fn pop(&mut self) -> Option<K> {
+        if self.spilled() {
+            return self.with_vec(Vec::pop);
+        }
+        self.capacity = self.capacity.checked_sub(1)?;
+        Some(unsafe { self.inline_ptr().add(self.capacity).read() })
+    }
+    /// Moves all elements of 'other' to the end of 'self'.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.spilled() {
+            self.extend(other.take_vec());
+            return;
+        }
+        let len = other.capacity;
+        other.capacity = 0;
+        for index in 0..len {
+            self.push(unsafe { other.inline_ptr().add(index).read() });
+        }
+    }
+    /// Splits off the elements starting at 'at', like [Vec::split_off].
+    pub fn split_off(&mut self, at: usize) -> Self {
+        if self.spilled() {
+            return Self::from_vec(self.with_vec(|vec| vec.split_off(at)));
+        }
+        assert!(at <= self.capacity, "`at` out of bounds");
+        let mut tail = Self::new();
+        unsafe {
+            let len = self.capacity - at;
+            ptr::copy_nonoverlapping(self.inline_ptr().add(at), tail.inline_mut_ptr(), len);
+            tail.capacity = len;
+        }
+        self.capacity = at;
+        tail
+    }
+    pub fn clear(&mut self) {
+        if self.spilled() {
+            self.with_vec(Vec::clear);
+        } else {
+            let len = self.capacity;
+            self.capacity = 0;
+            unsafe {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.inline_mut_ptr(), len))
+            };
+        }
+    }
+}
+impl<K> Drop for InlineVec<K> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+impl<K> Default for InlineVec<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<K> Deref for InlineVec<K> {
+    type Target = [K];
+    fn deref(&self) -> &Self::Target {
+        if self.spilled() {
+            let (ptr, len) = unsafe { self.data.heap };
+            unsafe { slice::from_raw_parts(ptr.as_ptr(), len) }
+        } else {
+            unsafe { slice::from_raw_parts(self.inline_ptr(), self.capacity) }
+        }
+    }
+}
+impl<K> DerefMut for InlineVec<K> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        if self.spilled() {
+            let (ptr, len) = unsafe { self.data.heap };
+            unsafe { slice::from_raw_parts_mut(ptr.as_ptr(), len) }
+        } else {
+            unsafe { slice::from_raw_parts_mut(self.inline_mut_ptr(), self.capacity) }
+        }
+    }
+}
+impl<K: Clone> Clone for InlineVec<K> {
+    fn clone(&self) -> Self {
+        Self::from_iter(self.iter().cloned())
+    }
+}
+impl<K: Debug> Debug for InlineVec<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+impl<K> Extend<K> for InlineVec<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for val in iter {
+            self.push(val);
+        }
+    }
+}
+impl<K> IntoIterator for InlineVec<K> {
+    type Item = K;
+    type IntoIter = vec::IntoIter<K>;
+    fn into_iter(mut self) -> Self::IntoIter {
+        self.take_vec().into_iter()
+    }
+}
+impl<K> FromIterator<K> for InlineVec<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut this = Self::new();
+        this.extend(iter);
+        this
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+    use std::rc::Rc;
+
+    #[test]
+    fn inline_bytes() {
+        assert_eq!(size_of::<InlineVec<u8>>(), size_of::<Vec<u8>>());
+        assert_eq!(InlineVec::<u8>::INLINE, 16);
+        assert_eq!(InlineVec::<u32>::INLINE, 4);
+        assert_eq!(InlineVec::<String>::INLINE, 0);
+        assert_eq!(InlineVec::<()>::INLINE, 0);
+        let mut prefix = InlineVec::from_iter(0..16u8);
+        assert!(!prefix.spilled());
+        prefix.push(16);
+        assert!(prefix.spilled());
+        assert!(!prefix.split_off(1).spilled());
+        assert!(!prefix.spilled());
+        assert!(InlineVec::from_iter([String::new()]).spilled());
+    }
+
+    #[quickcheck]
+    fn vec_oracle(ops: Vec<(u8, Vec<u8>, usize)>) {
+        // reference counts reveal any element dropped twice or never
+        let counted = Rc::new(());
+        check_against_vec(&ops, |val| val);
+        check_against_vec(&ops, |_| counted.clone());
+        check_against_vec(&ops, |val| (val, counted.clone()));
+        assert_eq!(Rc::strong_count(&counted), 1);
+    }
+
+    /// Every element type fitting inline a different number of times.
+    fn check_against_vec<T: Clone + PartialEq + Debug>(
+        ops: &[(u8, Vec<u8>, usize)],
+        wrap: impl Fn(u8) -> T,
+    ) {
+        let mut prefix = InlineVec::new();
+        let mut other = InlineVec::new();
+        let mut oracle = Vec::new();
+        for (op, vals, at) in ops {
+            let vals = Vec::from_iter(vals.iter().map(|&val| wrap(val)));
+            match op % 6 {
+                0 => {
+                    prefix.extend(vals.clone());
+                    oracle.extend(vals);
+                }
+                1 => assert_eq!(prefix.pop(), oracle.pop()),
+                2 => {
+                    let at = at % (oracle.len() + 1);
+                    assert_eq!(*prefix.split_off(at), oracle.split_off(at));
+                }
+                3 => {
+                    other.extend(vals.clone());
+                    oracle.extend(vals);
+                    prefix.append(&mut other);
+                    assert!(other.is_empty());
+                }
+                _ if op % 12 == 5 => {
+                    let moved = Vec::from_iter(prefix.split_off(0));
+                    prefix.extend(moved);
+                }
+                4 => {
+                    prefix.clear();
+                    oracle.clear();
+                }
+                _ => prefix = prefix.clone(),
+            }
+            assert_eq!(*prefix, oracle);
+            assert!(prefix.capacity() >= prefix.len());
+        }
+    }
+}
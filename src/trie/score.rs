@@ -0,0 +1,201 @@
+use crate::trie::{
+    Trie,
+    aug::{Summaries, Summarize},
+    branch::{BTreeBranch, Branch},
+    node::NodeHandle,
+    vnode::VNode,
+};
+use std::{borrow::Borrow, cmp::Reverse, collections::BinaryHeap, fmt::Debug};
+
+impl<K, V, B: Branch<K, V>> Trie<K, V, B> {
+    /// The 'k' values below 'prefix' with the highest score, highest first.
+    /// Ties keep iteration order.
+    ///
+    /// Scans the whole subtree, see [ScoreIndex] for repeated queries.
+    pub fn top_k<'a, Q: 'a + ?Sized + PartialEq, S: Ord>(
+        &self,
+        prefix: impl IntoIterator<Item = &'a Q>,
+        k: usize,
+        score: impl Fn(&V) -> S,
+    ) -> Vec<&V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let Some(subtrie) = self.subtrie(prefix).filter(|_| k > 0) else {
+            return vec![];
+        };
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for (i, value) in subtrie.iter().enumerate() {
+            heap.push(Reverse(Scored((score(value), Reverse(i)), value)));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        Vec::from_iter(
+            heap.into_sorted_vec()
+                .into_iter()
+                .map(|Reverse(Scored(_, value))| value),
+        )
+    }
+    /// Turns the [Trie] into a [ScoreIndex] ranking its values by 'score'.
+    pub fn into_score_index<S: Ord + Clone, F: Fn(&V) -> S>(
+        self,
+        score: F,
+    ) -> ScoreIndex<K, V, S, F, B> {
+        let best = Summaries::new(&self, &ByScore(&score));
+        ScoreIndex {
+            trie: self,
+            best,
+            score,
+        }
+    }
+}
+
+/// Compares by the score only.
+struct Scored<S, T>(S, T);
+impl<S: PartialEq, T> PartialEq for Scored<S, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+impl<S: Eq, T> Eq for Scored<S, T> {}
+impl<S: Ord, T> PartialOrd for Scored<S, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<S: Ord, T> Ord for Scored<S, T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+/// Either a value or a whole subtree, scored by its best value.
+type Candidate<'a, K, V, B, S> = Scored<(S, bool), Result<&'a V, NodeHandle<K, V, B>>>;
+
+/// Summarizes a subtree by the best score of its values.
+struct ByScore<'a, F>(&'a F);
+impl<V, S: Ord + Clone, F: Fn(&V) -> S> Summarize<V> for ByScore<'_, F> {
+    type Summary = S;
+    fn of(&self, value: &V) -> S {
+        (self.0)(value)
+    }
+    fn combine(&self, a: &S, b: &S) -> S {
+        a.max(b).clone()
+    }
+}
+
+/// [Trie] keeping the best score below every node up to date, so that
+/// [ScoreIndex::top_k] can search best-first and skip subtrees that can't compete.
+pub struct ScoreIndex<K, V, S, F, B = BTreeBranch<K, V>> {
+    trie: Trie<K, V, B>,
+    best: Summaries<S>,
+    score: F,
+}
+impl<K: Debug, V: Debug, S, F, B: Branch<K, V>> Debug for ScoreIndex<K, V, S, F, B>
+where
+    Trie<K, V, B>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ScoreIndex").field(&self.trie).finish()
+    }
+}
+impl<K, V, S: Ord + Clone, F: Fn(&V) -> S, B: Branch<K, V>> ScoreIndex<K, V, S, F, B> {
+    pub fn new(score: F) -> Self {
+        Trie::default().into_score_index(score)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+    pub fn insert(&mut self, key: impl IntoIterator<Item = K>, value: V) -> Option<V>
+    where
+        K: Clone + PartialEq,
+    {
+        let how = ByScore(&self.score);
+        self.best.insert(&mut self.trie, &how, key, value)
+    }
+    pub fn get<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        self.trie.get(key)
+    }
+    pub fn remove<'a, Q: 'a + ?Sized + PartialEq>(
+        &mut self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> Option<V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let how = ByScore(&self.score);
+        self.best.remove(&mut self.trie, &how, key)
+    }
+    pub fn clear(&mut self) {
+        self.trie.clear();
+        self.best.clear();
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &V> {
+        self.trie.iter()
+    }
+    pub fn as_trie(&self) -> &Trie<K, V, B> {
+        &self.trie
+    }
+    pub fn into_trie(self) -> Trie<K, V, B> {
+        self.trie
+    }
+    /// The 'k' values below 'prefix' with the highest score, highest first.
+    /// Order among ties is unspecified.
+    ///
+    /// Only expands nodes whose best score is among the 'k' highest,
+    /// so it costs O(k · (depth + fan-out) · log) instead of O(subtree).
+    pub fn top_k<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        prefix: impl IntoIterator<Item = &'a Q>,
+        k: usize,
+    ) -> Vec<&V>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let trie = &self.trie;
+        let mut ret = vec![];
+        let Ok(start) = VNode::start(trie.root.leak()).descend(trie, prefix, |_, _, _| true) else {
+            return ret;
+        };
+        let start = start.skip_prefix(trie).is_node_handle(&trie.nodes);
+        let mut heap = BinaryHeap::from_iter(self.candidate(start));
+        while ret.len() < k
+            && let Some(Scored(_, candidate)) = heap.pop()
+        {
+            match candidate {
+                Ok(value) => ret.push(value),
+                Err(handle) => {
+                    let node = VNode::start(handle.leak()).skip_prefix(trie);
+                    heap.extend(node.leaf(trie).map(|value| {
+                        // values go before subtrees with the same bound
+                        Scored(((self.score)(value), true), Ok(value))
+                    }));
+                    let children = node
+                        .branch(trie)
+                        .into_iter()
+                        .flat_map(Branch::<K, V>::values);
+                    heap.extend(children.flat_map(|child| self.candidate(child)));
+                }
+            }
+        }
+        ret
+    }
+    fn candidate(&self, handle: NodeHandle<K, V, B>) -> Option<Candidate<'_, K, V, B, S>> {
+        let best = self.best.get(&handle)?.clone();
+        Some(Scored((best, false), Err(handle)))
+    }
+}
@@ -0,0 +1,246 @@
+use crate::trie::{
+    Trie,
+    branch::{BTreeBranch, Branch},
+    vnode::VNode,
+};
+use std::{borrow::Borrow, fmt::Debug, ops::ControlFlow};
+
+/// Set of keys backed by a [Trie].
+///
+/// Membership is only marked inside the nodes themselves, so no leaves are ever allocated.
+/// Set operations walk both tries in lockstep instead of looking up every key.
+pub struct TrieSet<K, B = BTreeBranch<K, ()>> {
+    trie: Trie<K, (), B>,
+    len: usize,
+}
+impl<K, B> Default for TrieSet<K, B> {
+    fn default() -> Self {
+        Self {
+            trie: Default::default(),
+            len: 0,
+        }
+    }
+}
+impl<K: Clone + Debug, B: Branch<K, ()>> Debug for TrieSet<K, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+impl<K: Clone + PartialEq, B: Branch<K, ()>> PartialEq for TrieSet<K, B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.is_subset(other)
+    }
+}
+impl<K: PartialEq, B: Branch<K, ()>, I: IntoIterator<Item = K>> FromIterator<I> for TrieSet<K, B> {
+    fn from_iter<T: IntoIterator<Item = I>>(iter: T) -> Self {
+        let mut this = Self::default();
+        this.extend(iter);
+        this
+    }
+}
+impl<K: PartialEq, B: Branch<K, ()>, I: IntoIterator<Item = K>> Extend<I> for TrieSet<K, B> {
+    fn extend<T: IntoIterator<Item = I>>(&mut self, iter: T) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+impl<K, B: Branch<K, ()>> TrieSet<K, B> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns whether 'key' was newly inserted.
+    pub fn insert(&mut self, key: impl IntoIterator<Item = K>) -> bool
+    where
+        K: PartialEq,
+    {
+        let inserted = VNode::start(self.trie.root.leak())
+            .make_descend(&mut self.trie, key)
+            .make_mark(&mut self.trie);
+        self.len += inserted as usize;
+        inserted
+    }
+    pub fn contains<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> bool
+    where
+        K: Borrow<Q>,
+        B: Branch<K, (), Q>,
+    {
+        VNode::start(self.trie.root.leak())
+            .descend(&self.trie, key, |_, _, _| true)
+            .is_ok_and(|node| node.marked(&self.trie))
+    }
+    /// Whether any prefix of 'key', including itself, is contained.
+    pub fn contains_prefix_of<'a, Q: 'a + ?Sized + PartialEq>(
+        &self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> bool
+    where
+        K: Borrow<Q>,
+        B: Branch<K, (), Q>,
+    {
+        VNode::start(self.trie.root.leak())
+            .find(&self.trie, key, |found, trie| {
+                match found.value.marked(trie) {
+                    true => Ok(()),
+                    false => Err(None),
+                }
+            })
+            .is_ok()
+    }
+    /// Returns whether 'key' was contained.
+    pub fn remove<'a, Q: 'a + ?Sized + PartialEq>(
+        &mut self,
+        key: impl IntoIterator<Item = &'a Q>,
+    ) -> bool
+    where
+        K: Borrow<Q>,
+        B: Branch<K, (), Q>,
+    {
+        let removed = VNode::start(self.trie.root.leak())
+            .dive(
+                &mut self.trie,
+                key,
+                |_, _, _| true,
+                |node, trie| {
+                    let node = node.take_mark(trie)?;
+                    node.prune_branch(trie);
+                    Some(())
+                },
+                |node, trie, _| node.prune_branch(trie),
+            )
+            .is_ok();
+        self.len -= removed as usize;
+        removed
+    }
+    pub fn clear(&mut self) {
+        self.trie.clear();
+        self.len = 0;
+    }
+    pub fn iter(&self) -> impl Iterator<Item = Vec<K>>
+    where
+        K: Clone,
+    {
+        VNode::start(self.trie.root.leak()).iter_marked(&self.trie)
+    }
+    pub fn is_subset(&self, other: &Self) -> bool
+    where
+        K: Clone + PartialEq,
+    {
+        self.len <= other.len
+            && self
+                .zip(other, (true, false), |_, mine, theirs| {
+                    match mine && !theirs {
+                        true => ControlFlow::Break(()),
+                        false => ControlFlow::Continue(()),
+                    }
+                })
+                .is_continue()
+    }
+    pub fn is_disjoint(&self, other: &Self) -> bool
+    where
+        K: Clone + PartialEq,
+    {
+        self.zip(other, (false, false), |_, mine, theirs| {
+            match mine && theirs {
+                true => ControlFlow::Break(()),
+                false => ControlFlow::Continue(()),
+            }
+        })
+        .is_continue()
+    }
+    pub fn union(&self, other: &Self) -> Self
+    where
+        K: Clone + PartialEq,
+    {
+        self.collect(other, (true, true), |_, _| true)
+    }
+    pub fn intersection(&self, other: &Self) -> Self
+    where
+        K: Clone + PartialEq,
+    {
+        self.collect(other, (false, false), |mine, theirs| mine && theirs)
+    }
+    pub fn difference(&self, other: &Self) -> Self
+    where
+        K: Clone + PartialEq,
+    {
+        self.collect(other, (true, false), |mine, theirs| mine && !theirs)
+    }
+    pub fn symmetric_difference(&self, other: &Self) -> Self
+    where
+        K: Clone + PartialEq,
+    {
+        self.collect(other, (true, true), |mine, theirs| mine != theirs)
+    }
+    /// The keys of 'self' and 'other' for which 'keep' holds, see [TrieSet::zip].
+    fn collect(&self, other: &Self, walk: (bool, bool), keep: impl Fn(bool, bool) -> bool) -> Self
+    where
+        K: Clone + PartialEq,
+    {
+        let mut ret = Self::new();
+        let _ = self.zip(other, walk, |key, mine, theirs| {
+            if keep(mine, theirs) {
+                ret.insert(key.iter().cloned());
+            }
+            ControlFlow::<()>::Continue(())
+        });
+        ret
+    }
+    /// Walks 'self' and 'other' in lockstep, calling 'visit' with every key contained in either,
+    /// together with whether it is contained in 'self' and in 'other'.
+    /// Subtrees only one of them has are skipped, unless 'walk' asks for those of 'self' or 'other'.
+    fn zip<T>(
+        &self,
+        other: &Self,
+        (walk_mine, walk_theirs): (bool, bool),
+        mut visit: impl FnMut(&[K], bool, bool) -> ControlFlow<T>,
+    ) -> ControlFlow<T>
+    where
+        K: Clone + PartialEq,
+    {
+        let (mine, theirs) = (&self.trie, &other.trie);
+        let mut key = vec![];
+        let mut stack: Vec<(usize, Option<&K>, _, _)> = vec![(
+            0,
+            None,
+            Some(VNode::start(mine.root.leak())),
+            Some(VNode::start(theirs.root.leak())),
+        )];
+        while let Some((len, k, a, b)) = stack.pop() {
+            key.truncate(len);
+            key.extend(k.cloned());
+            let (in_a, in_b) = (
+                a.as_ref().is_some_and(|a| a.marked(mine)),
+                b.as_ref().is_some_and(|b| b.marked(theirs)),
+            );
+            if in_a || in_b {
+                visit(&key, in_a, in_b)?;
+            }
+            if let Some(a) = &a {
+                for k in a.child_keys(mine) {
+                    let b = b.as_ref().and_then(|b| b.next(theirs, k));
+                    if walk_mine || b.is_some() {
+                        stack.push((key.len(), Some(k), a.next(mine, k), b));
+                    }
+                }
+            }
+            if let Some(b) = b.as_ref().filter(|_| walk_theirs) {
+                for k in b.child_keys(theirs) {
+                    if a.as_ref().is_none_or(|a| a.next(mine, k).is_none()) {
+                        stack.push((key.len(), Some(k), None, b.next(theirs, k)));
+                    }
+                }
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "testing")]
+
+use crate::{
+    testing::Shape,
+    trie::{Trie, branch::Branch, node::NodeHandle},
+};
+
+impl<'a, K: Clone + Ord, V, B: Branch<K, V>> From<&'a Trie<K, V, B>> for Shape<'a, K, V> {
+    fn from(trie: &'a Trie<K, V, B>) -> Self {
+        let shape = |handle: &NodeHandle<K, V, B>| {
+            let node = handle.get(&trie.nodes);
+            Shape {
+                prefix: node.prefix().to_vec(),
+                value: node._get_leaf(&trie.leaves).map(|leaf| leaf.get()),
+                children: vec![],
+            }
+        };
+        let mut ret = shape(&trie.root);
+        // path of child indices from the root down to each node still to visit
+        let mut stack = vec![(Vec::<usize>::new(), trie.root.leak())];
+        while let Some((path, handle)) = stack.pop() {
+            let Some(branch) = handle.get(&trie.nodes).get_branch(&trie.branches) else {
+                continue;
+            };
+            let mut children = Vec::from_iter(Branch::<K, V>::iter(branch));
+            children.sort_unstable_by_key(|(key, _)| *key);
+            let mut parent = &mut ret;
+            for &i in &path {
+                parent = &mut parent.children[i].1;
+            }
+            for (i, (key, child)) in children.into_iter().enumerate() {
+                parent.children.push((key.clone(), shape(&child)));
+                stack.push(([&path[..], &[i]].concat(), child));
+            }
+        }
+        ret
+    }
+}
@@ -0,0 +1,241 @@
+use crate::trie::storage::Store;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "checked-handles")]
+use std::any::type_name;
+use std::{marker::PhantomData, mem::replace, ops::DerefMut};
+
+#[derive(Debug, Clone)]
+pub enum Entry<T> {
+    /// Index of the next vacant slot.
+    Vacant(usize),
+    Occupied(T),
+}
+#[derive(Debug, Clone)]
+pub struct Slot<T> {
+    entry: Entry<T>,
+    #[cfg(feature = "checked-handles")]
+    generation: u32,
+}
+
+/// Growable list a [Slots] keeps its [Slot]s in.
+pub trait SlotVec<T>: DerefMut<Target = [T]> {
+    fn push(&mut self, val: T);
+    fn clear(&mut self);
+    fn capacity(&self) -> usize;
+}
+impl<T> SlotVec<T> for Vec<T> {
+    fn push(&mut self, val: T) {
+        Vec::push(self, val)
+    }
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+    fn capacity(&self) -> usize {
+        Vec::capacity(self)
+    }
+}
+
+/// [Store] reusing vacated slots for later insertions, so that indices of occupied ones stay put.
+///
+/// With the 'checked-handles' feature every slot also counts how often it has been vacated,
+/// so that handles into it can be checked for still pointing at what they were created for.
+#[derive(Debug, Clone)]
+pub struct Slots<T, S = Vec<Slot<T>>> {
+    slots: S,
+    len: usize,
+    /// Head of the list of vacant slots, 'slots.len()' if there are none.
+    next: usize,
+    _slot: PhantomData<T>,
+}
+impl<T> Default for Slots<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Slots<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_slots(Vec::with_capacity(capacity))
+    }
+    /// Like mapping every entry, keeping both indices and generations.
+    pub fn try_map<U, E>(self, mut f: impl FnMut(T) -> Result<U, E>) -> Result<Slots<U>, E> {
+        let slots = self.slots.into_iter().map(|slot| {
+            Ok(Slot {
+                entry: match slot.entry {
+                    Entry::Vacant(next) => Entry::Vacant(next),
+                    Entry::Occupied(val) => Entry::Occupied(f(val)?),
+                },
+                #[cfg(feature = "checked-handles")]
+                generation: slot.generation,
+            })
+        });
+        Ok(Slots {
+            slots: slots.collect::<Result<_, E>>()?,
+            len: self.len,
+            next: self.next,
+            _slot: PhantomData,
+        })
+    }
+}
+impl<T, S: SlotVec<Slot<T>>> Slots<T, S> {
+    /// Starts out empty in 'slots', which must be empty as well.
+    pub fn with_slots(slots: S) -> Self {
+        debug_assert!(slots.is_empty());
+        Self {
+            slots,
+            len: 0,
+            next: 0,
+            _slot: PhantomData,
+        }
+    }
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| match &slot.entry {
+                Entry::Occupied(val) => Some((index, val)),
+                Entry::Vacant(_) => None,
+            })
+    }
+    /// Splits the slots into disjoint chunks, handing out every occupied one exactly once.
+    #[cfg(feature = "rayon")]
+    pub fn par_iter_mut(&mut self) -> impl ParallelIterator<Item = &mut T>
+    where
+        T: Send,
+    {
+        self.slots
+            .par_iter_mut()
+            .filter_map(|slot| match &mut slot.entry {
+                Entry::Occupied(val) => Some(val),
+                Entry::Vacant(_) => None,
+            })
+    }
+}
+impl<T, S: SlotVec<Slot<T>>> Store<T> for Slots<T, S> {
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn vacant_key(&self) -> usize {
+        self.next
+    }
+    fn insert(&mut self, val: T) -> usize {
+        let index = self.next;
+        let entry = Entry::Occupied(val);
+        match self.slots.get_mut(index) {
+            None => {
+                self.slots.push(Slot {
+                    entry,
+                    #[cfg(feature = "checked-handles")]
+                    generation: 0,
+                });
+                self.next = self.slots.len();
+            }
+            Some(slot) => match replace(&mut slot.entry, entry) {
+                Entry::Vacant(next) => self.next = next,
+                Entry::Occupied(_) => unreachable!(),
+            },
+        }
+        self.len += 1;
+        index
+    }
+    fn remove(&mut self, index: usize) -> T {
+        let slot = &mut self.slots[index];
+        let Entry::Occupied(_) = slot.entry else {
+            panic!("invalid key");
+        };
+        let Entry::Occupied(val) = replace(&mut slot.entry, Entry::Vacant(self.next)) else {
+            unreachable!()
+        };
+        #[cfg(feature = "checked-handles")]
+        {
+            slot.generation = slot.generation.wrapping_add(1);
+        }
+        self.next = index;
+        self.len -= 1;
+        val
+    }
+    fn clear(&mut self) {
+        #[cfg(feature = "checked-handles")]
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if let Entry::Occupied(_) = slot.entry {
+                slot.generation = slot.generation.wrapping_add(1);
+            }
+            slot.entry = Entry::Vacant(index + 1);
+        }
+        #[cfg(not(feature = "checked-handles"))]
+        self.slots.clear();
+        self.next = 0;
+        self.len = 0;
+    }
+    fn get(&self, index: usize) -> &T {
+        match &self.slots[index].entry {
+            Entry::Occupied(val) => val,
+            Entry::Vacant(_) => panic!("invalid key"),
+        }
+    }
+    fn get_mut(&mut self, index: usize) -> &mut T {
+        match &mut self.slots[index].entry {
+            Entry::Occupied(val) => val,
+            Entry::Vacant(_) => panic!("invalid key"),
+        }
+    }
+    #[cfg(feature = "checked-handles")]
+    fn generation(&self, index: usize) -> u32 {
+        self.slots.get(index).map_or(0, |slot| slot.generation)
+    }
+    #[cfg(feature = "checked-handles")]
+    fn check(&self, index: usize, generation: u32) {
+        let occupied = matches!(
+            self.slots.get(index),
+            Some(Slot {
+                entry: Entry::Occupied(_),
+                ..
+            })
+        );
+        check::<T>(index, generation, self.generation(index), occupied);
+    }
+}
+
+/// Panics unless the slot at 'index' is 'occupied' and still at 'generation'.
+#[cfg(feature = "checked-handles")]
+pub(super) fn check<T>(index: usize, generation: u32, current: u32, occupied: bool) {
+    if current != generation || !occupied {
+        panic!(
+            "stale handle to {} at {index}: created at generation {generation}, slot is {} at generation {current}",
+            type_name::<T>(),
+            if occupied { "occupied" } else { "vacant" },
+        );
+    }
+}
+
+#[cfg(all(test, feature = "checked-handles"))]
+mod tests {
+    use crate::trie::{handle::Handle, storage::Store};
+
+    #[test]
+    #[should_panic(expected = "stale handle")]
+    fn use_after_remove() {
+        let mut shared = Handle::new_shared();
+        let stale = Handle::new(&mut shared, "removed");
+        stale.leak().remove(&mut shared);
+        let reused = Handle::new(&mut shared, "reused");
+        assert_eq!(stale.leak()._unwrap(), reused.leak()._unwrap());
+        assert_eq!(*reused.get(&shared), "reused");
+        stale.get(&shared);
+    }
+
+    #[test]
+    #[should_panic(expected = "slot is vacant")]
+    fn use_after_clear() {
+        let mut shared = Handle::new_shared();
+        let stale = Handle::new(&mut shared, 0);
+        shared.clear();
+        stale.get(&shared);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::trie::{
+    Leaf, Trie,
+    branch::{Branch, HeapSize},
+    handle::Shared,
+    node::Node,
+    storage::Store,
+};
+use std::collections::BTreeMap;
+
+/// Live entries of one of a [Trie]'s [Shared]s, next to how many it has room for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SlabStats {
+    pub live: usize,
+    pub capacity: usize,
+}
+impl SlabStats {
+    fn of<T>(shared: &Shared<T>) -> Self {
+        Self {
+            live: shared.len(),
+            capacity: shared.capacity(),
+        }
+    }
+}
+
+/// Structure and memory usage of a [Trie], see [Trie::stats].
+///
+/// Histograms map each observed size to how often it occurred.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub nodes: SlabStats,
+    pub branches: SlabStats,
+    pub leaves: SlabStats,
+    /// Prefix length of every node.
+    pub prefix_lengths: BTreeMap<usize, usize>,
+    /// Number of children of every branch.
+    pub fan_out: BTreeMap<usize, usize>,
+    /// Number of nodes below the root on the way to every value.
+    pub depths: BTreeMap<usize, usize>,
+    /// Estimated bytes allocated for the [Trie] itself,
+    /// excluding allocations owned by its keys and values.
+    pub heap_bytes: usize,
+}
+
+impl<K, V, B: Branch<K, V> + HeapSize> Trie<K, V, B> {
+    /// Walks the whole [Trie] to collect its [Stats].
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats {
+            nodes: SlabStats::of(&self.nodes),
+            branches: SlabStats::of(&self.branches),
+            leaves: SlabStats::of(&self.leaves),
+            heap_bytes: self.nodes.capacity() * size_of::<Node<K, V, B>>()
+                + self.branches.capacity() * size_of::<B>()
+                + self.leaves.capacity() * size_of::<Leaf<V>>(),
+            ..Default::default()
+        };
+        let mut stack = vec![(self.root.leak(), 0)];
+        while let Some((handle, depth)) = stack.pop() {
+            let node = handle.get(&self.nodes);
+            *stats.prefix_lengths.entry(node.prefix().len()).or_default() += 1;
+            stats.heap_bytes += node.prefix_heap_size();
+            if node.leaf().is_some() {
+                *stats.depths.entry(depth).or_default() += 1;
+            }
+            if let Some(branch) = node.get_branch(&self.branches) {
+                let len = stack.len();
+                stack.extend(Branch::<K, V>::values(branch).map(|child| (child, depth + 1)));
+                *stats.fan_out.entry(stack.len() - len).or_default() += 1;
+                stats.heap_bytes += branch.heap_size();
+            }
+        }
+        stats
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(feature = "allocator-api2")]
+use crate::trie::arena::Arena;
+use crate::trie::{handle::Shared, paged::Paged};
+#[cfg(feature = "allocator-api2")]
+use allocator_api2::alloc::Allocator;
+
+/// Slots [Handle](super::handle::Handle)s point into, reusing vacated ones for later insertions.
+pub trait Store<T> {
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Index the next [Store::insert] puts its value at.
+    fn vacant_key(&self) -> usize;
+    fn insert(&mut self, val: T) -> usize;
+    fn remove(&mut self, index: usize) -> T;
+    fn clear(&mut self);
+    fn get(&self, index: usize) -> &T;
+    fn get_mut(&mut self, index: usize) -> &mut T;
+    /// Generation the slot at 'index' is currently at, or will be once occupied.
+    #[cfg(feature = "checked-handles")]
+    fn generation(&self, index: usize) -> u32;
+    /// Panics if 'index' doesn't point at an occupied slot still at 'generation'.
+    #[cfg(feature = "checked-handles")]
+    fn check(&self, index: usize, generation: u32);
+}
+
+/// Where a [Trie](super::Trie) keeps its nodes, branches and leaves.
+pub trait Storage {
+    type Store<T>: Store<T>;
+    fn store<T>(&self, capacity: usize) -> Self::Store<T>;
+}
+
+/// The default [Storage], one [Slots](super::slots::Slots) each.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Slabs;
+impl Storage for Slabs {
+    type Store<T> = Shared<T>;
+    fn store<T>(&self, capacity: usize) -> Self::Store<T> {
+        Shared::with_capacity(capacity)
+    }
+}
+
+/// [Storage] whose copies share all their entries until written to, see [CowTrie](super::CowTrie).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Pages;
+impl Storage for Pages {
+    type Store<T> = Paged<T>;
+    fn store<T>(&self, _capacity: usize) -> Self::Store<T> {
+        Paged::new()
+    }
+}
+
+/// [Storage] allocating every slot from 'A', see [Trie::new_in](super::Trie::new_in).
+///
+/// Node prefixes and branches still use the global allocator for what they own themselves.
+#[cfg(feature = "allocator-api2")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Arenas<A>(pub A);
+#[cfg(feature = "allocator-api2")]
+impl<A: Allocator + Clone> Storage for Arenas<A> {
+    type Store<T> = Arena<T, A>;
+    fn store<T>(&self, capacity: usize) -> Self::Store<T> {
+        Arena::with_capacity_in(capacity, self.0.clone())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::trie::{
+    Trie,
+    branch::Branch,
+    storage::{Slabs, Storage},
+    vnode::VNode,
+};
+use std::borrow::Borrow;
+
+/// Read-only view of all entries of a [Trie] sharing a common prefix.
+pub struct SubTrie<'a, K, V, B, S: Storage = Slabs> {
+    trie: &'a Trie<K, V, B, S>,
+    vnode: VNode<K, V, B>,
+}
+impl<'a, K, V, B: Branch<K, V>, S: Storage> SubTrie<'a, K, V, B, S> {
+    pub(super) fn new(trie: &'a Trie<K, V, B, S>, vnode: VNode<K, V, B>) -> Self {
+        Self { trie, vnode }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.vnode.empty_node(&self.trie.nodes)
+    }
+    /// Value stored exactly at the common prefix.
+    pub fn value(&self) -> Option<&'a V> {
+        self.vnode.leaf(self.trie)
+    }
+    /// Narrows this view further down by 'prefix', relative to the current one.
+    pub fn subtrie<'k, Q: 'k + ?Sized + PartialEq>(
+        &self,
+        prefix: impl IntoIterator<Item = &'k Q>,
+    ) -> Option<Self>
+    where
+        K: Borrow<Q>,
+        B: Branch<K, V, Q>,
+    {
+        let vnode = self.vnode.descend(self.trie, prefix, |_, _, _| true).ok()?;
+        Some(Self::new(self.trie, vnode))
+    }
+    /// Keys leading one step further down, e.g. the next segment for path-like keys.
+    pub fn children(&self) -> impl use<'a, K, V, B, S> + Iterator<Item = &'a K> {
+        self.vnode.child_keys(self.trie)
+    }
+    pub fn iter(&self) -> impl use<'a, K, V, B, S> + Iterator<Item = &'a V> {
+        self.vnode.iter(self.trie)
+    }
+    /// Iterates over all entries, with keys relative to the common prefix.
+    pub fn iter_with_keys(&self) -> impl use<'a, K, V, B, S> + Iterator<Item = (Vec<K>, &'a V)>
+    where
+        K: Clone,
+    {
+        self.vnode.iter_keyed(self.trie)
+    }
+    pub fn keys(&self) -> impl use<'a, K, V, B, S> + Iterator<Item = Vec<K>>
+    where
+        K: Clone,
+    {
+        self.iter_with_keys().map(|(k, _)| k)
+    }
+}
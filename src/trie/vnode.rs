@@ -1,17 +1,31 @@
 use crate::trie::{
-    LeafHandle, Trie,
+    Leaf, LeafHandle, Match, Trie,
     branch::Branch,
-    handle::{Handle, Shared},
-    node::{Node, NodeHandle},
+    handle::Handle,
+    node::{DataHandle, Node, NodeHandle, Prefix},
+    storage::{Storage, Store},
 };
 use std::{
     borrow::Borrow,
     cmp::Ordering,
     fmt::Debug,
+    hash::Hash,
     iter::{self, Peekable},
     mem::{replace, take, transmute},
 };
 
+/// Told about the nodes [VNode]s move or free while restructuring a [Trie],
+/// so that data kept alongside them can follow, see [AugTrie](super::AugTrie).
+pub trait Observer<K, V, B> {
+    /// Everything below 'from' now lives below 'to' instead.
+    fn moved(&mut self, from: &NodeHandle<K, V, B>, to: &NodeHandle<K, V, B>);
+    fn removed(&mut self, node: &NodeHandle<K, V, B>);
+}
+impl<K, V, B> Observer<K, V, B> for () {
+    fn moved(&mut self, _: &NodeHandle<K, V, B>, _: &NodeHandle<K, V, B>) {}
+    fn removed(&mut self, _: &NodeHandle<K, V, B>) {}
+}
+
 pub struct VNode<K, V, B> {
     prefix_len: usize,
     handle: NodeHandle<K, V, B>,
@@ -29,6 +43,13 @@ impl<K, V, B> PartialEq for VNode<K, V, B> {
         self.prefix_len == other.prefix_len && self.handle == other.handle
     }
 }
+impl<K, V, B> Eq for VNode<K, V, B> {}
+impl<K, V, B> Hash for VNode<K, V, B> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.prefix_len.hash(state);
+        self.handle.hash(state);
+    }
+}
 /// Navigation methods
 impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
     pub fn start(root: NodeHandle<K, V, B>) -> Self {
@@ -43,7 +64,11 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
             handle: self.handle.leak(),
         }
     }
-    pub fn next<Q: PartialEq>(&self, trie: &Trie<K, V, B>, key: &Q) -> Option<Self>
+    pub fn next<Q: ?Sized + PartialEq, S: Storage>(
+        &self,
+        trie: &Trie<K, V, B, S>,
+        key: &Q,
+    ) -> Option<Self>
     where
         K: Borrow<Q>,
         B: Branch<K, V, Q>,
@@ -62,7 +87,7 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
             None => None,
         }
     }
-    pub fn _make_next(&self, trie: &mut Trie<K, V, B>, key: K) -> Self
+    pub fn _make_next<S: Storage>(&self, trie: &mut Trie<K, V, B, S>, key: K) -> Self
     where
         K: PartialEq,
     {
@@ -78,7 +103,7 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
             }
         } else {
             Self::start(
-                self.make_branch(trie)
+                self.make_branch(trie, &mut ())
                     .get_mut(&mut trie.branches)
                     .get_or_insert_with(key, || {
                         Handle::new_with(&mut trie.nodes, |_t| {
@@ -86,7 +111,7 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
                                 #[cfg(feature = "testing")]
                                 _t,
                                 self.handle.leak(),
-                                vec![],
+                                Prefix::new(),
                                 (),
                             )
                         })
@@ -94,7 +119,22 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
             )
         }
     }
-    pub fn make_descend(&self, trie: &mut Trie<K, V, B>, key: impl IntoIterator<Item = K>) -> Self
+    pub fn make_descend<S: Storage>(
+        &self,
+        trie: &mut Trie<K, V, B, S>,
+        key: impl IntoIterator<Item = K>,
+    ) -> Self
+    where
+        K: PartialEq,
+    {
+        self.make_descend_observed(trie, key, &mut ())
+    }
+    pub fn make_descend_observed<S: Storage>(
+        &self,
+        trie: &mut Trie<K, V, B, S>,
+        key: impl IntoIterator<Item = K>,
+        observer: &mut impl Observer<K, V, B>,
+    ) -> Self
     where
         K: PartialEq,
     {
@@ -109,13 +149,18 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
                     .extend(key);
                 break vnode.skip_prefix(trie);
             }
-            vnode = vnode.try_skip_prefix(trie, &mut key, K::eq);
+            (vnode, _) = vnode.try_skip_prefix(trie, &mut key, K::eq);
             let Some(key) = key.next() else {
                 break vnode;
             };
+            // only write to what changes, so that snapshots keep sharing the rest
+            if let Some(next) = vnode.next(trie, &key) {
+                vnode = next;
+                continue;
+            }
             vnode = Self::start(
                 vnode
-                    .make_branch(trie)
+                    .make_branch(trie, observer)
                     .get_mut(&mut trie.branches)
                     .get_or_insert_with(key, || {
                         Handle::new_with(&mut trie.nodes, |_t| {
@@ -123,7 +168,7 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
                                 #[cfg(feature = "testing")]
                                 _t,
                                 self.handle.leak(),
-                                vec![],
+                                Prefix::new(),
                                 (),
                             )
                         })
@@ -136,11 +181,11 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
     /// excluding the target returned inside [Result::Ok].
     /// A returned [Result::Err] will contain the [VNode] that has either been rejected
     /// by inspect, or where 'key' pointed towards a non-existent branch.
-    pub fn descend<'a, Q: 'a + PartialEq>(
+    pub fn descend<'a, Q: 'a + ?Sized + PartialEq, S: Storage>(
         &self,
-        trie: &Trie<K, V, B>,
+        trie: &Trie<K, V, B, S>,
         key: impl IntoIterator<Item = &'a Q>,
-        mut inspect: impl FnMut(Self, &Trie<K, V, B>, &'a Q) -> bool,
+        mut inspect: impl FnMut(Self, &Trie<K, V, B, S>, &'a Q) -> bool,
     ) -> Result<Self, Self>
     where
         K: Borrow<Q>,
@@ -149,7 +194,7 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
         let mut key = key.into_iter().peekable();
         let mut node = self.leak();
         loop {
-            node = node.try_skip_prefix(trie, &mut key, |k, q| k.borrow() == *q);
+            (node, _) = node.try_skip_prefix(trie, &mut key, |k, q| k.borrow() == *q);
             let Some(key) = key.next() else {
                 break Ok(node);
             };
@@ -164,11 +209,11 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
     /// excluding the target returned inside [Result::Ok].
     /// A returned [Result::Err] will contain the [VNode] that has either been rejected
     /// by inspect, or where 'key' pointed towards a non-existent branch.
-    pub fn descend_mut<'a, Q: 'a + PartialEq>(
+    pub fn descend_mut<'a, Q: 'a + ?Sized + PartialEq, S: Storage>(
         &self,
-        trie: &mut Trie<K, V, B>,
+        trie: &mut Trie<K, V, B, S>,
         key: impl IntoIterator<Item = &'a Q>,
-        mut inspect: impl FnMut(Self, &mut Trie<K, V, B>, &'a Q) -> bool,
+        mut inspect: impl FnMut(Self, &mut Trie<K, V, B, S>, &'a Q) -> bool,
     ) -> Result<Self, Self>
     where
         K: Borrow<Q>,
@@ -177,7 +222,7 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
         let mut key = key.into_iter().peekable();
         let mut node = self.leak();
         loop {
-            node = node.try_skip_prefix(trie, &mut key, |k, q| k.borrow() == *q);
+            (node, _) = node.try_skip_prefix(trie, &mut key, |k, q| k.borrow() == *q);
             let Some(key) = key.next() else {
                 break Ok(node);
             };
@@ -188,18 +233,19 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
         }
     }
     /// Searches for a value inside [Trie] along a path determined by 'key'.
-    /// 'f' will be called with all [VNode]s encountered along the way.
+    /// 'f' will be called with all [VNode]s encountered along the way, wrapped in a [Match]
+    /// carrying the number of elements of 'key' consumed and whether it ran out there.
     /// When this returns [Result::Ok] it will be returned immediately.
     /// When [Result::Err] is returned instead, search will continue,
     /// with the value inside [Option::Some] being kept as a backup,
     /// should the search otherwise fail and [Option::None] keeping the previous one.
     /// When 'f' never succeeds nor gives a backup, [Result::Err] will contain the [VNode] where 'key'
     /// ran out or pointed towards a non-existent branch.
-    pub fn find<'a, Q: 'a + PartialEq, T>(
+    pub fn find<'a, Q: 'a + ?Sized + PartialEq, T, S: Storage>(
         &self,
-        trie: &Trie<K, V, B>,
+        trie: &Trie<K, V, B, S>,
         key: impl IntoIterator<Item = &'a Q>,
-        mut f: impl FnMut(Self, &Trie<K, V, B>) -> Result<T, Option<T>>,
+        mut f: impl FnMut(Match<Self>, &Trie<K, V, B, S>) -> Result<T, Option<T>>,
     ) -> Result<T, Self>
     where
         K: Borrow<Q>,
@@ -208,9 +254,17 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
         let mut node = self.leak();
         let mut backup = None;
         let mut key = key.into_iter().peekable();
+        let mut depth = 0;
         loop {
-            node = node.try_skip_prefix(trie, &mut key, |k, q| k.borrow() == *q);
-            match f(node.leak(), trie) {
+            let skipped;
+            (node, skipped) = node.try_skip_prefix(trie, &mut key, |k, q| k.borrow() == *q);
+            depth += skipped;
+            let found = Match {
+                depth,
+                value: node.leak(),
+                exact: key.peek().is_none(),
+            };
+            match f(found, trie) {
                 Ok(t) => break Ok(t),
                 Err(t @ Some(_)) => backup = t,
                 Err(None) => (),
@@ -219,6 +273,7 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
                 && let Some(next) = node.next(trie, k)
             {
                 node = next;
+                depth += 1;
             } else {
                 break backup.ok_or(node.leak());
             }
@@ -236,13 +291,13 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
     /// A returned [Result::Err] will contain the [VNode] that has either been rejected
     /// by 'inspect_descend', where 'key' pointed towards a non-existent branch,
     /// or 'inspect_target' returned [Option::None].
-    pub fn dive<'a, T, Q: 'a + PartialEq>(
+    pub fn dive<'a, T, Q: 'a + ?Sized + PartialEq, S: Storage>(
         &self,
-        trie: &mut Trie<K, V, B>,
+        trie: &mut Trie<K, V, B, S>,
         key: impl IntoIterator<Item = &'a Q>,
-        mut inspect_descend: impl FnMut(Self, &mut Trie<K, V, B>, &'a Q) -> bool,
-        inspect_target: impl FnOnce(Self, &mut Trie<K, V, B>) -> Option<T>,
-        mut inspect_ascend: impl FnMut(Self, &mut Trie<K, V, B>, &'a Q) -> bool,
+        mut inspect_descend: impl FnMut(Self, &mut Trie<K, V, B, S>, &'a Q) -> bool,
+        inspect_target: impl FnOnce(Self, &mut Trie<K, V, B, S>) -> Option<T>,
+        mut inspect_ascend: impl FnMut(Self, &mut Trie<K, V, B, S>, &'a Q) -> bool,
     ) -> Result<T, Self>
     where
         K: Borrow<Q>,
@@ -262,22 +317,22 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
         }
         Ok(ret)
     }
-    pub fn snap_prefix(&self, trie: &Trie<K, V, B>) -> Self {
+    pub fn snap_prefix<S: Storage>(&self, trie: &Trie<K, V, B, S>) -> Self {
         let handle = self.handle.leak();
         let prefix_len = self.prefix_len.min(handle.get(&trie.nodes).prefix().len());
         Self { prefix_len, handle }
     }
-    pub fn skip_prefix(&self, trie: &Trie<K, V, B>) -> Self {
+    pub fn skip_prefix<S: Storage>(&self, trie: &Trie<K, V, B, S>) -> Self {
         let handle = self.handle.leak();
         let prefix_len = handle.get(&trie.nodes).prefix().len();
         Self { prefix_len, handle }
     }
-    pub fn try_skip_prefix<Q: PartialEq>(
+    pub fn try_skip_prefix<Q: PartialEq, S: Storage>(
         &self,
-        trie: &Trie<K, V, B>,
+        trie: &Trie<K, V, B, S>,
         key: &mut Peekable<impl Iterator<Item = Q>>,
         eq: impl Fn(&K, &Q) -> bool,
-    ) -> Self {
+    ) -> (Self, usize) {
         let handle = self.handle.leak();
         let remaining_prefix = &handle.get(&trie.nodes).prefix()[self.prefix_len..];
         let mut match_len = 0;
@@ -286,12 +341,16 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
         {
             match_len += 1;
         }
-        Self {
+        let node = Self {
             prefix_len: self.prefix_len + match_len,
             handle,
-        }
+        };
+        (node, match_len)
     }
-    pub fn into_iter(&self, mut trie: Trie<K, V, B>) -> impl use<K, V, B> + Iterator<Item = V> {
+    pub fn into_iter<S: Storage>(
+        &self,
+        mut trie: Trie<K, V, B, S>,
+    ) -> impl use<K, V, B, S> + Iterator<Item = V> {
         let mut stack = vec![self.leak()];
         iter::from_fn(move || {
             loop {
@@ -305,10 +364,10 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
             }
         })
     }
-    pub fn iter<'a>(
+    pub fn iter<'a, S: Storage>(
         &self,
-        trie: &'a Trie<K, V, B>,
-    ) -> impl use<'a, K, V, B> + Iterator<Item = &'a V> {
+        trie: &'a Trie<K, V, B, S>,
+    ) -> impl use<'a, K, V, B, S> + Iterator<Item = &'a V> {
         let mut stack = vec![self.leak()];
         iter::from_fn(move || {
             loop {
@@ -323,10 +382,10 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
         })
     }
 
-    pub fn iter_mut<'a>(
+    pub fn iter_mut<'a, S: Storage>(
         &self,
-        trie: &'a mut Trie<K, V, B>,
-    ) -> impl use<'a, K, V, B> + Iterator<Item = &'a mut V> {
+        trie: &'a mut Trie<K, V, B, S>,
+    ) -> impl use<'a, K, V, B, S> + Iterator<Item = &'a mut V> {
         let mut stack = vec![self.leak()];
         iter::from_fn(move || {
             loop {
@@ -344,6 +403,85 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
             }
         })
     }
+    /// Keys leading from 'self' one step further down.
+    pub fn child_keys<'a, S: Storage>(
+        &self,
+        trie: &'a Trie<K, V, B, S>,
+    ) -> impl use<'a, K, V, B, S> + Iterator<Item = &'a K> {
+        let (next, branch) = match self.as_node(&trie.nodes) {
+            None => (
+                Some(&self.handle.get(&trie.nodes).prefix()[self.prefix_len]),
+                None,
+            ),
+            Some(node) => (None, node.get_branch(&trie.branches)),
+        };
+        next.into_iter()
+            .chain(branch.into_iter().flat_map(|branch| branch.keys()))
+    }
+    /// Like [VNode::iter], but also reconstructs each key relative to 'self'.
+    pub fn iter_keyed<'a, S: Storage>(
+        &self,
+        trie: &'a Trie<K, V, B, S>,
+    ) -> impl use<'a, K, V, B, S> + Iterator<Item = (Vec<K>, &'a V)>
+    where
+        K: Clone,
+    {
+        self.iter_keyed_handles(trie)
+            .map(|(key, leaf)| (key, leaf.get(&trie.leaves).get()))
+    }
+    /// Like [VNode::iter_keyed], without looking into the leaves themselves.
+    pub fn iter_keyed_handles<'a, S: Storage>(
+        &self,
+        trie: &'a Trie<K, V, B, S>,
+    ) -> impl use<'a, K, V, B, S> + Iterator<Item = (Vec<K>, LeafHandle<V>)>
+    where
+        K: Clone,
+    {
+        self.iter_keyed_by(trie, Self::leaf_handle)
+    }
+    /// Keys relative to 'self' marked by [VNode::make_mark].
+    pub fn iter_marked<'a, S: Storage>(
+        &self,
+        trie: &'a Trie<K, V, B, S>,
+    ) -> impl use<'a, K, V, B, S> + Iterator<Item = Vec<K>>
+    where
+        K: Clone,
+    {
+        self.iter_keyed_by(trie, |node, trie| node.marked(trie).then_some(()))
+            .map(|(key, ())| key)
+    }
+    fn iter_keyed_by<'a, T, F: FnMut(&Self, &'a Trie<K, V, B, S>) -> Option<T>, S: Storage>(
+        &self,
+        trie: &'a Trie<K, V, B, S>,
+        mut found: F,
+    ) -> impl use<'a, K, V, B, T, F, S> + Iterator<Item = (Vec<K>, T)>
+    where
+        K: Clone,
+    {
+        let mut key = vec![];
+        let mut stack: Vec<(usize, Option<&'a K>, Self)> = vec![(0, None, self.leak())];
+        iter::from_fn(move || {
+            loop {
+                let (len, k, node) = stack.pop()?;
+                key.truncate(len);
+                key.extend(k.cloned());
+                key.extend_from_slice(&node.handle.get(&trie.nodes).prefix()[node.prefix_len..]);
+                let node = node.skip_prefix(trie);
+                if let Some(branch) = node.branch(trie) {
+                    let len = stack.len();
+                    stack.extend(
+                        branch
+                            .iter()
+                            .map(|(k, child)| (key.len(), Some(k), Self::start(child))),
+                    );
+                    stack[len..].reverse();
+                }
+                if let Some(found) = found(&node, trie) {
+                    break Some((key.clone(), found));
+                }
+            }
+        })
+    }
     fn stack_extend(stack: &mut Vec<Self>, branch: &B) {
         let len = stack.len();
         stack.extend(branch.values().map(|node| Self {
@@ -356,87 +494,151 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
 
 /// Manipulation methods
 impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
-    pub fn empty_node(&self, nodes: &Shared<Node<K, V, B>>) -> bool {
+    pub fn empty_node(&self, nodes: &impl Store<Node<K, V, B>>) -> bool {
         self.handle.get(nodes).is_empty()
     }
-    pub fn is_node_handle(&self, nodes: &Shared<Node<K, V, B>>) -> NodeHandle<K, V, B> {
+    pub fn is_node_handle(&self, nodes: &impl Store<Node<K, V, B>>) -> NodeHandle<K, V, B> {
         self.as_node_handle(nodes)
             .expect("VNode isn't actual Node.")
     }
-    pub fn is_node<'a>(&self, nodes: &'a Shared<Node<K, V, B>>) -> &'a Node<K, V, B> {
+    pub fn is_node<'a>(&self, nodes: &'a impl Store<Node<K, V, B>>) -> &'a Node<K, V, B> {
         self.is_node_handle(nodes).get(nodes)
     }
-    pub fn is_node_mut<'a>(&self, nodes: &'a mut Shared<Node<K, V, B>>) -> &'a mut Node<K, V, B> {
+    pub fn is_node_mut<'a>(
+        &self,
+        nodes: &'a mut impl Store<Node<K, V, B>>,
+    ) -> &'a mut Node<K, V, B> {
         self.is_node_handle(nodes).get_mut(nodes)
     }
-    pub fn as_node_handle(&self, nodes: &Shared<Node<K, V, B>>) -> Option<NodeHandle<K, V, B>> {
+    pub fn as_node_handle(&self, nodes: &impl Store<Node<K, V, B>>) -> Option<NodeHandle<K, V, B>> {
         match self.prefix_len.cmp(&self.handle.get(nodes).prefix().len()) {
             Ordering::Less => None,
             Ordering::Equal => Some(self.handle.leak()),
             Ordering::Greater => panic!("Invalid VNode"),
         }
     }
-    pub fn as_node<'a>(&self, nodes: &'a Shared<Node<K, V, B>>) -> Option<&'a Node<K, V, B>> {
+    pub fn as_node<'a>(&self, nodes: &'a impl Store<Node<K, V, B>>) -> Option<&'a Node<K, V, B>> {
         Some(self.as_node_handle(nodes)?.get(nodes))
     }
     pub fn as_node_mut<'a>(
         &self,
-        nodes: &'a mut Shared<Node<K, V, B>>,
+        nodes: &'a mut impl Store<Node<K, V, B>>,
     ) -> Option<&'a mut Node<K, V, B>> {
         Some(self.as_node_handle(nodes)?.get_mut(nodes))
     }
-    pub fn make_leaf(&self, trie: &mut Trie<K, V, B>, value: V) -> Option<V> {
+    pub fn make_leaf<S: Storage>(&self, trie: &mut Trie<K, V, B, S>, value: V) -> Option<V> {
+        self.make_leaf_observed(trie, value, &mut ())
+    }
+    pub fn make_leaf_observed<S: Storage>(
+        &self,
+        trie: &mut Trie<K, V, B, S>,
+        value: V,
+        observer: &mut impl Observer<K, V, B>,
+    ) -> Option<V> {
+        let mut value = Some(value);
+        let leaf = self.make_leaf_with_observed(trie, observer, |leaves| {
+            Handle::new(
+                leaves,
+                Leaf::new(
+                    #[cfg(feature = "testing")]
+                    self.handle.leak(),
+                    value.take().unwrap(),
+                ),
+            )
+        });
+        let leaf = leaf.err()?;
+        Some(leaf.get_mut(&mut trie.leaves).replace(value.unwrap()))
+    }
+    /// Like [VNode::make_leaf], but with the leaf created by 'new', see [Node::make_leaf_with].
+    pub fn make_leaf_with_observed<S: Storage>(
+        &self,
+        trie: &mut Trie<K, V, B, S>,
+        observer: &mut impl Observer<K, V, B>,
+        new: impl FnOnce(&mut S::Store<Leaf<V>>) -> LeafHandle<V>,
+    ) -> Result<(), LeafHandle<V>> {
+        self.split(trie, observer);
+        let leaves = &mut trie.leaves;
+        self.handle
+            .get_mut(&mut trie.nodes)
+            .make_leaf_with(|| new(leaves))
+    }
+    /// Marks the key leading to 'self' as present without a leaf, see [DataHandle::Marked].
+    /// Returns whether it wasn't already.
+    pub fn make_mark<S: Storage>(&self, trie: &mut Trie<K, V, B, S>) -> bool {
+        self.split(trie, &mut ());
+        self.handle.get_mut(&mut trie.nodes).make_mark()
+    }
+    pub fn marked<S: Storage>(&self, trie: &Trie<K, V, B, S>) -> bool {
+        self.as_node(&trie.nodes).is_some_and(Node::is_marked)
+    }
+    pub fn take_mark<S: Storage>(&self, trie: &mut Trie<K, V, B, S>) -> Option<Self> {
+        let marked = self.as_node_mut(&mut trie.nodes)?.take_mark();
+        marked.then(|| self.snap_prefix(trie))
+    }
+    /// Makes 'self' an actual [Node] by splitting off the rest of its prefix.
+    fn split<S: Storage>(
+        &self,
+        trie: &mut Trie<K, V, B, S>,
+        observer: &mut impl Observer<K, V, B>,
+    ) {
         let Trie {
             root: _,
             nodes,
             branches,
-            leaves,
+            leaves: _leaves,
         } = trie;
-        let node = self.handle.get_mut(nodes);
-        match self.prefix_len.cmp(&node.prefix().len()) {
-            Ordering::Less => {
-                let (value, new_node) =
-                    node.make_leaf_at(self.handle.leak(), branches, leaves, value, self.prefix_len);
-                if let Some((key, mut new_node)) = new_node {
-                    debug_assert!(!new_node.is_empty());
-                    let new_node = Handle::new_with(nodes, |_this| {
-                        #[cfg(feature = "testing")]
-                        new_node.set_this(_this, branches, leaves);
-                        new_node
-                    });
-                    self.handle
-                        .get_mut(nodes)
-                        .get_branch_mut(branches)
-                        .unwrap()
-                        .insert(key, new_node);
-                }
-                value
-            }
-            Ordering::Equal => node.make_leaf(
+        let new_node =
+            self.handle
+                .get_mut(nodes)
+                .split_at(self.handle.leak(), branches, self.prefix_len);
+        if let Some((key, mut new_node)) = new_node {
+            debug_assert!(!new_node.is_empty());
+            let new_node = Handle::new_with(nodes, |_this| {
                 #[cfg(feature = "testing")]
-                self.handle.leak(),
-                leaves,
-                value,
-            ),
-            Ordering::Greater => unreachable!(),
+                new_node.set_this(_this, branches, _leaves);
+                new_node
+            });
+            observer.moved(&self.handle, &new_node);
+            self.handle
+                .get_mut(nodes)
+                .get_branch_mut(branches)
+                .unwrap()
+                .insert(key, new_node);
         }
     }
-    pub fn leaf_handle(&self, trie: &Trie<K, V, B>) -> Option<LeafHandle<V>> {
+    pub fn leaf_handle<S: Storage>(&self, trie: &Trie<K, V, B, S>) -> Option<LeafHandle<V>> {
         self.as_node(&trie.nodes)?.leaf()
     }
-    pub fn leaf<'a>(&self, trie: &'a Trie<K, V, B>) -> Option<&'a V> {
+    pub fn leaf<'a, S: Storage>(&self, trie: &'a Trie<K, V, B, S>) -> Option<&'a V> {
         Some(self.leaf_handle(trie)?.get(&trie.leaves).get())
     }
-    pub fn leaf_mut<'a>(&self, trie: &'a mut Trie<K, V, B>) -> Option<&'a mut V> {
+    pub fn leaf_mut<'a, S: Storage>(&self, trie: &'a mut Trie<K, V, B, S>) -> Option<&'a mut V> {
         Some(self.leaf_handle(trie)?.get_mut(&mut trie.leaves).get_mut())
     }
-    pub fn take_leaf(&self, trie: &mut Trie<K, V, B>) -> Option<(Self, V)> {
+    pub fn take_leaf<S: Storage>(&self, trie: &mut Trie<K, V, B, S>) -> Option<(Self, V)> {
         let ret = self
             .as_node_mut(&mut trie.nodes)?
             .take_leaf(&mut trie.leaves)?;
         Some((self.snap_prefix(trie), ret))
     }
-    pub fn make_branch(&self, trie: &mut Trie<K, V, B>) -> Handle<B> {
+    /// Detaches everything from 'self' downwards, without removing it from its [Store]s.
+    /// Returns the remainder of the prefix together with the detached data.
+    pub fn detach<S: Storage>(
+        &self,
+        trie: &mut Trie<K, V, B, S>,
+    ) -> Option<(Self, Prefix<K>, DataHandle<V, B>)> {
+        let node = self.handle.get_mut(&mut trie.nodes);
+        if node.is_empty() {
+            return None;
+        }
+        let (prefix, data) = node.take_data(self.prefix_len);
+        Some((self.snap_prefix(trie), prefix, data))
+    }
+    pub fn make_branch<S: Storage>(
+        &self,
+        trie: &mut Trie<K, V, B, S>,
+        observer: &mut impl Observer<K, V, B>,
+    ) -> Handle<B> {
         let Trie {
             root: _,
             nodes,
@@ -455,6 +657,7 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
                         new_node.set_this(_this, branches, _leaves);
                         new_node
                     });
+                    observer.moved(&self.handle, &new_node);
                     self.handle
                         .get_mut(nodes)
                         .get_branch_mut(branches)
@@ -471,38 +674,44 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
             Ordering::Greater => unreachable!(),
         }
     }
-    pub fn _branch_handle(&self, trie: &Trie<K, V, B>) -> Option<Handle<B>> {
+    pub fn _branch_handle<S: Storage>(&self, trie: &Trie<K, V, B, S>) -> Option<Handle<B>> {
         self.as_node(&trie.nodes)?.branch()
     }
-    pub fn branch<'a>(&self, trie: &'a Trie<K, V, B>) -> Option<&'a B> {
+    pub fn branch<'a, S: Storage>(&self, trie: &'a Trie<K, V, B, S>) -> Option<&'a B> {
         self.as_node(&trie.nodes)?.get_branch(&trie.branches)
     }
-    pub fn _branch_mut<'a>(&mut self, trie: &'a mut Trie<K, V, B>) -> Option<&'a mut B> {
+    pub fn _branch_mut<'a, S: Storage>(
+        &mut self,
+        trie: &'a mut Trie<K, V, B, S>,
+    ) -> Option<&'a mut B> {
         self.as_node_mut(&mut trie.nodes)?
             .get_branch_mut(&mut trie.branches)
     }
-    fn prune_messy(
+    fn prune_messy<S: Storage>(
         &self,
-        trie: &mut Trie<K, V, B>,
+        trie: &mut Trie<K, V, B, S>,
+        observer: &mut impl Observer<K, V, B>,
     ) -> Option<(Option<()>, Handle<B>, Option<(K, NodeHandle<K, V, B>)>)> {
-        let (leaf, branch) = self.is_node(&trie.nodes).leaf_branch();
+        let node = self.is_node(&trie.nodes);
         // only prune if self is branch
-        let branch = branch?;
+        let branch = node.branch()?;
         Some((
-            leaf.map(|_| ()),
+            node.has_key().then_some(()),
             branch.leak(),
             // try to prune
             branch.get_mut(&mut trie.branches).prune(|handle| {
                 if handle.get_null(&trie.nodes).is_some_and(Node::is_empty) {
+                    observer.removed(handle);
                     replace(handle, Handle::new_null()).remove(&mut trie.nodes);
                 }
                 handle.is_null()
             })?,
         ))
     }
-    fn prune_cleanup(
+    fn prune_cleanup<S: Storage>(
         &self,
-        trie: &mut Trie<K, V, B>,
+        trie: &mut Trie<K, V, B, S>,
+        observer: &mut impl Observer<K, V, B>,
         leaf: Option<()>,
         branch: Handle<B>,
         displaced: Option<(K, NodeHandle<K, V, B>)>,
@@ -510,7 +719,7 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
         match (leaf, displaced) {
             // self is now empty, contract displaced into self
             (None, Some((key, displaced))) => {
-                self.prune_contract(trie, key, displaced);
+                self.prune_contract(trie, observer, key, displaced);
             }
             // leaf means self is not empty, have to restore displaced as single child
             (Some(_), Some((key, displaced))) => {
@@ -528,7 +737,13 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
             }
         }
     }
-    fn prune_contract(&self, trie: &mut Trie<K, V, B>, key: K, displaced: NodeHandle<K, V, B>) {
+    fn prune_contract<S: Storage>(
+        &self,
+        trie: &mut Trie<K, V, B, S>,
+        observer: &mut impl Observer<K, V, B>,
+        key: K,
+        displaced: NodeHandle<K, V, B>,
+    ) {
         let Trie {
             root: _,
             nodes,
@@ -536,6 +751,7 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
             leaves: _leaves,
         } = trie;
         // get node to contract into self
+        observer.moved(&displaced, &self.handle);
         let mut displaced = displaced.remove(nodes);
         let node = self.is_node_mut(nodes);
         // update displaced previous and this node to own
@@ -545,15 +761,22 @@ impl<K, V, B: Branch<K, V>> VNode<K, V, B> {
         // update displaced prefix to [own_prefix.., key, ..displaced_prefix]
         let (tmp, prefix) = (node.prefix_mut(), displaced.prefix_mut());
         tmp.push(key);
-        tmp.extend(prefix.drain(..));
+        tmp.append(prefix);
         *prefix = take(tmp);
         // replace empty self with displaced node
         let old = replace(node, displaced).branch().unwrap().remove(branches);
         debug_assert!(old.is_empty());
     }
-    pub fn prune_branch(&self, trie: &mut Trie<K, V, B>) -> bool {
-        if let Some((leaf, branch, displaced)) = self.prune_messy(trie) {
-            self.prune_cleanup(trie, leaf, branch, displaced);
+    pub fn prune_branch<S: Storage>(&self, trie: &mut Trie<K, V, B, S>) -> bool {
+        self.prune_branch_observed(trie, &mut ())
+    }
+    pub fn prune_branch_observed<S: Storage>(
+        &self,
+        trie: &mut Trie<K, V, B, S>,
+        observer: &mut impl Observer<K, V, B>,
+    ) -> bool {
+        if let Some((leaf, branch, displaced)) = self.prune_messy(trie, observer) {
+            self.prune_cleanup(trie, observer, leaf, branch, displaced);
         }
         self.empty_node(&trie.nodes)
     }
@@ -0,0 +1,64 @@
+#![cfg(feature = "allocator-api2")]
+
+use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+use buffman_tree::{
+    Arenas, Trie,
+    branch::{BTreeBranch, ByteBranch},
+};
+use quickcheck_macros::quickcheck;
+use std::{cell::Cell, collections::BTreeMap, ptr::NonNull};
+
+/// Forwards to [Global], keeping track of how many bytes are currently allocated through it.
+#[derive(Default)]
+struct Counting {
+    live: Cell<usize>,
+}
+unsafe impl Allocator for &Counting {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.live.set(self.live.get() + layout.size());
+        Global.allocate(layout)
+    }
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - layout.size());
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+#[test]
+fn new_in() {
+    let alloc = Counting::default();
+    let mut trie: Trie<u8, u32, BTreeBranch<u8, u32>, Arenas<&Counting>> = Trie::new_in(&alloc);
+    assert!(alloc.live.get() > 0);
+    let empty = alloc.live.get();
+    for i in 0..100u32 {
+        assert_eq!(trie.insert(i.to_be_bytes(), i), None);
+    }
+    assert!(alloc.live.get() > empty);
+    assert_eq!(trie.get(&7u32.to_be_bytes()), Some(&7));
+    assert_eq!(trie.remove(&7u32.to_be_bytes()), Some(7));
+    assert_eq!(trie.len(), 99);
+    trie.clear();
+    assert!(trie.is_empty());
+    drop(trie);
+    assert_eq!(alloc.live.get(), 0);
+}
+
+#[quickcheck]
+fn arena_oracle(ops: Vec<(bool, Vec<u8>, u8)>) {
+    let small = |bytes: Vec<u8>| Vec::from_iter(bytes.into_iter().take(4).map(|b| b % 3));
+    let mut trie: Trie<u8, u8, ByteBranch<u8>, Arenas<Global>> = Trie::new_in(Global);
+    let mut oracle = BTreeMap::new();
+    for (insert, key, value) in ops {
+        let key = small(key);
+        if insert {
+            assert_eq!(trie.insert(key.clone(), value), oracle.insert(key, value));
+        } else {
+            assert_eq!(trie.remove(&key), oracle.remove(&key));
+        }
+    }
+    assert_eq!(trie.len(), oracle.len());
+    assert_eq!(
+        Vec::from_iter(trie.iter_with_keys().map(|(k, v)| (k, *v))),
+        Vec::from_iter(oracle)
+    );
+}
@@ -0,0 +1,131 @@
+use buffman_tree::{Aggregate, AugTrie, branch::HashBranch};
+use quickcheck_macros::quickcheck;
+use std::{
+    collections::BTreeMap,
+    ops::{Bound, RangeBounds},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Sum(u64);
+impl Aggregate<u64> for Sum {
+    fn empty() -> Self {
+        Self(0)
+    }
+    fn of(value: &u64) -> Self {
+        Self(*value)
+    }
+    fn combine(&self, other: &Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+/// Not commutative, so also checks the order values are combined in.
+#[derive(Debug, Clone, PartialEq)]
+struct Concat(Vec<u64>);
+impl Aggregate<u64> for Concat {
+    fn empty() -> Self {
+        Self(vec![])
+    }
+    fn of(value: &u64) -> Self {
+        Self(vec![*value])
+    }
+    fn combine(&self, other: &Self) -> Self {
+        Self([&self.0[..], &other.0].concat())
+    }
+}
+
+#[test]
+fn sums() {
+    let mut trie: AugTrie<u8, u64, Sum> = AugTrie::from_iter([
+        (b"apple".to_vec(), 3),
+        (b"apricot".to_vec(), 5),
+        (b"banana".to_vec(), 7),
+        (b"ap".to_vec(), 11),
+    ]);
+    assert_eq!(trie.aggregate(), Sum(26));
+    assert_eq!(trie.aggregate_prefix(b"ap"), Sum(19));
+    assert_eq!(trie.aggregate_prefix(b"apr"), Sum(5));
+    assert_eq!(trie.aggregate_prefix(b"b"), Sum(7));
+    assert_eq!(trie.aggregate_prefix(b"c"), Sum(0));
+    assert_eq!(trie.aggregate_range(&b"apple"[..]..&b"b"[..]), Sum(8));
+    assert_eq!(trie.aggregate_range(..=&b"ap"[..]), Sum(11));
+    assert_eq!(trie.aggregate_range(&b"apricot"[..]..), Sum(12));
+
+    assert_eq!(trie.remove(b"apricot"), Some(5));
+    assert_eq!(trie.aggregate_prefix(b"ap"), Sum(14));
+    assert_eq!(trie.aggregate_prefix(b"apr"), Sum(0));
+    trie.insert(b"a".to_vec(), 100);
+    assert_eq!(trie.aggregate_prefix(b"a"), Sum(114));
+    trie.clear();
+    assert_eq!(trie.aggregate(), Sum(0));
+}
+
+#[quickcheck]
+fn aggregate_oracle(ops: Vec<(bool, Vec<u8>, u64)>, queries: Vec<(Vec<u8>, Vec<u8>)>) {
+    let small = |bytes: Vec<u8>| Vec::from_iter(bytes.into_iter().take(4).map(|b| b % 3));
+    let mut trie: AugTrie<u8, u64, Concat> = AugTrie::new();
+    let mut map = BTreeMap::new();
+    for (insert, key, value) in ops {
+        let key = small(key);
+        if insert {
+            assert_eq!(trie.insert(key.clone(), value), map.insert(key, value));
+        } else {
+            assert_eq!(trie.remove(&key), map.remove(&key));
+        }
+        assert_eq!(
+            trie.aggregate(),
+            Concat(Vec::from_iter(map.values().copied()))
+        );
+    }
+    for (a, b) in queries {
+        let (a, b) = (small(a), small(b));
+        let expected = Vec::from_iter(
+            map.iter()
+                .filter(|(key, _)| key.starts_with(&a))
+                .map(|(_, v)| *v),
+        );
+        assert_eq!(trie.aggregate_prefix(&a), Concat(expected));
+
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        for bounds in [
+            (Bound::Included(&low[..]), Bound::Excluded(&high[..])),
+            (Bound::Excluded(&low[..]), Bound::Included(&high[..])),
+            (Bound::Unbounded, Bound::Included(&high[..])),
+            (Bound::Excluded(&low[..]), Bound::Unbounded),
+        ] {
+            let expected = Vec::from_iter(
+                map.iter()
+                    .filter(|(key, _)| (bounds.0, bounds.1).contains(&key[..]))
+                    .map(|(_, v)| *v),
+            );
+            assert_eq!(trie.aggregate_range::<[u8]>(bounds), Concat(expected));
+        }
+    }
+}
+
+#[quickcheck]
+fn unordered_oracle(ops: Vec<(bool, Vec<u8>, u8)>) {
+    let small = |bytes: Vec<u8>| Vec::from_iter(bytes.into_iter().take(4).map(|b| b % 3));
+    let mut trie: AugTrie<u8, u64, Sum, HashBranch<u8, u64>> = AugTrie::new();
+    let mut map = BTreeMap::new();
+    for (insert, key, value) in ops {
+        let (key, value) = (small(key), u64::from(value));
+        if insert {
+            assert_eq!(
+                trie.insert(key.clone(), value),
+                map.insert(key.clone(), value)
+            );
+        } else {
+            assert_eq!(trie.remove(&key), map.remove(&key));
+        }
+        // every prefix of 'key' is on the path that was just updated
+        for len in 0..=key.len() {
+            let expected = map
+                .iter()
+                .filter(|(k, _)| k.starts_with(&key[..len]))
+                .map(|(_, v)| v)
+                .sum();
+            assert_eq!(trie.aggregate_prefix(&key[..len]), Sum(expected));
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use buffman_tree::{
     Trie,
     branch::{BTreeBranch, Branch, ByteBranch},
-    testing::{Action, Op, Procedure},
+    testing::{Action, Op, Procedure, Shape},
 };
 use quickcheck::TestResult;
 use std::{
@@ -19,6 +19,34 @@ fn btree_oracle() {
         .quickcheck(test::<ByteBranch<_>> as fn(Procedure<(Vec<u8>, usize)>) -> TestResult);
 }
 
+#[cfg(feature = "rayon")]
+#[test]
+fn par_from_iter_oracle() {
+    fn test<B: Branch<u8, usize> + Send>(items: Vec<(Vec<u8>, usize)>) -> TestResult {
+        let items = Vec::from_iter(
+            items
+                .into_iter()
+                .map(|(key, value)| (Vec::from_iter(key.iter().take(6).map(|k| k % 4)), value)),
+        );
+        let sequential: Trie<_, _, B> = Trie::from_iter(items.clone());
+        let parallel: Trie<_, _, B> = Trie::par_from_iter(items.clone());
+        let oracle = BTreeMap::from_iter(items);
+        TestResult::from_bool(
+            Shape::from(&parallel) == Shape::from(&sequential)
+                && parallel.len() == oracle.len()
+                && oracle
+                    .iter()
+                    .all(|(key, value)| parallel.get(key) == Some(value)),
+        )
+    }
+    quickcheck::QuickCheck::new()
+        .tests(0x400)
+        .quickcheck(test::<BTreeBranch<_, _>> as fn(Vec<(Vec<u8>, usize)>) -> TestResult);
+    quickcheck::QuickCheck::new()
+        .tests(0x400)
+        .quickcheck(test::<ByteBranch<_>> as fn(Vec<(Vec<u8>, usize)>) -> TestResult);
+}
+
 #[test]
 fn btree_oracle_cases() {
     let cases = [
@@ -0,0 +1,40 @@
+use buffman_tree::{CapacityError, Trie, branch::ByteBranch};
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeMap;
+
+#[test]
+fn try_insert() {
+    let mut trie: Trie<u8, u32> = Trie::default();
+    assert_eq!(trie.try_insert(b"foo".iter().copied(), 1), Ok(None));
+    assert_eq!(trie.try_insert(b"fo".iter().copied(), 2), Ok(None));
+    assert_eq!(trie.try_insert(b"foo".iter().copied(), 3), Ok(Some(1)));
+    assert_eq!(trie.get(b"fo"), Some(&2));
+    assert_eq!(trie.get(b"foo"), Some(&3));
+    assert!(
+        CapacityError
+            .to_string()
+            .starts_with("trie exceeds the capacity")
+    );
+}
+
+#[quickcheck]
+fn try_insert_oracle(ops: Vec<(bool, Vec<u8>, u8)>) {
+    let small = |bytes: Vec<u8>| Vec::from_iter(bytes.into_iter().take(4).map(|b| b % 3));
+    let mut trie: Trie<u8, u8, ByteBranch<u8>> = Trie::default();
+    let mut oracle = BTreeMap::new();
+    for (insert, key, value) in ops {
+        let key = small(key);
+        if insert {
+            assert_eq!(
+                trie.try_insert(key.clone(), value),
+                Ok(oracle.insert(key, value))
+            );
+        } else {
+            assert_eq!(trie.remove(&key), oracle.remove(&key));
+        }
+    }
+    assert_eq!(
+        Vec::from_iter(trie.iter_with_keys().map(|(k, v)| (k, *v))),
+        Vec::from_iter(oracle)
+    );
+}
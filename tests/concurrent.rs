@@ -0,0 +1,149 @@
+#![cfg(feature = "concurrent")]
+
+use buffman_tree::{ConcurrentTrie, PersistentTrie, branch::HashBranch};
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+#[test]
+fn concurrent() {
+    let trie: ConcurrentTrie<u8, &str> = ConcurrentTrie::new();
+    assert_eq!(trie.insert(b"/api".iter().copied(), "api"), None);
+    assert_eq!(trie.insert(b"/".iter().copied(), "root"), None);
+    let snapshot = trie.snapshot();
+    assert_eq!(trie.insert(b"/api".iter().copied(), "API"), Some("api"));
+    assert_eq!(trie.get_deepest(b"/api/users"), Some("API"));
+    assert_eq!(snapshot.get_deepest(b"/api/users"), Some(&"api"));
+    assert_eq!(trie.remove(b"/api"), Some("API"));
+    assert_eq!(trie.remove(b"/api"), None);
+    assert_eq!(trie.get_deepest(b"/api/users"), Some("root"));
+    assert_eq!(trie.len(), 1);
+}
+
+#[test]
+fn unordered_keys() {
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum Segment {
+        Static(&'static str),
+        Param,
+    }
+    use Segment::*;
+    let trie: ConcurrentTrie<Segment, u32, HashBranch<Segment, u32>> = ConcurrentTrie::new();
+    trie.insert([Static("users"), Param], 1);
+    trie.insert([Static("users"), Param, Static("posts")], 2);
+    assert_eq!(trie.get(&[Static("users"), Param]), Some(1));
+    assert_eq!(trie.get_deepest(&[Static("users"), Param, Param]), Some(1));
+    assert_eq!(trie.remove(&[Static("users"), Param]), Some(1));
+    assert_eq!(
+        trie.get_deepest(&[Static("users"), Param, Static("posts")]),
+        Some(2)
+    );
+}
+
+#[test]
+fn readers_see_writes_in_order() {
+    const N: u32 = 2000;
+    let key = |i: u32| i.to_be_bytes();
+    let trie: ConcurrentTrie<u8, u32> = ConcurrentTrie::new();
+    let done = AtomicBool::new(false);
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                let mut seen = 0;
+                while !done.load(Ordering::Acquire) {
+                    // once a write is visible, all earlier ones have to be as well
+                    let latest = (seen..N)
+                        .take_while(|&i| trie.get(&key(i)).is_some())
+                        .last();
+                    if let Some(latest) = latest {
+                        for i in 0..=latest {
+                            assert_eq!(trie.get(&key(i)), Some(i));
+                        }
+                        seen = latest + 1;
+                    }
+                }
+            });
+        }
+        s.spawn(|| {
+            for i in 0..N {
+                assert_eq!(trie.insert(key(i), i), None);
+            }
+            done.store(true, Ordering::Release);
+        });
+    });
+    assert_eq!(trie.len(), N as usize);
+}
+
+#[test]
+fn writers_dont_lose_updates() {
+    const THREADS: u32 = 8;
+    const N: u32 = 500;
+    let trie: ConcurrentTrie<u8, u32> = ConcurrentTrie::new();
+    thread::scope(|s| {
+        for t in 0..THREADS {
+            let trie = &trie;
+            s.spawn(move || {
+                for i in 0..N {
+                    let key = (t * N + i).to_be_bytes();
+                    trie.insert(key, i);
+                    if i % 2 == 1 {
+                        assert_eq!(trie.remove(&key), Some(i));
+                    }
+                    // contended counter, only correct if updates are serialized
+                    trie.update(|trie| {
+                        let count = trie.get(b"count").copied().unwrap_or(0);
+                        trie.insert(b"count".iter().copied(), count + 1)
+                    });
+                }
+            });
+        }
+    });
+    assert_eq!(trie.len(), (THREADS * N / 2 + 1) as usize);
+    assert_eq!(trie.get(b"count"), Some(THREADS * N));
+}
+
+#[test]
+fn snapshots_stay_consistent() {
+    // writers move units between accounts, so every version has to hold all of them
+    const ACCOUNTS: u8 = 16;
+    const UNITS: u32 = 100;
+    const MOVES: u32 = 2000;
+    let trie: ConcurrentTrie<u8, u32> = ConcurrentTrie::from(PersistentTrie::from_iter(
+        (0..ACCOUNTS).map(|a| ([a, a], UNITS)),
+    ));
+    let done = AtomicBool::new(false);
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                while !done.load(Ordering::Acquire) {
+                    let snapshot = trie.snapshot();
+                    assert_eq!(snapshot.len(), ACCOUNTS as usize);
+                    assert_eq!(snapshot.iter().sum::<u32>(), ACCOUNTS as u32 * UNITS);
+                }
+            });
+        }
+        let writers = Vec::from_iter((0..4u32).map(|w| {
+            let trie = &trie;
+            s.spawn(move || {
+                for i in 0..MOVES {
+                    let from = ((w * MOVES + i) % ACCOUNTS as u32) as u8;
+                    let to = ((w * 7 + i * 3 + 1) % ACCOUNTS as u32) as u8;
+                    trie.update(|trie| {
+                        let moved = trie.get(&[from, from]).copied().unwrap().min(1);
+                        *trie.get_mut(&[from, from]).unwrap() -= moved;
+                        *trie.get_mut(&[to, to]).unwrap() += moved;
+                    });
+                }
+            })
+        }));
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        done.store(true, Ordering::Release);
+    });
+    let total = (0..ACCOUNTS)
+        .map(|a| trie.get(&[a, a]).unwrap())
+        .sum::<u32>();
+    assert_eq!(total, ACCOUNTS as u32 * UNITS);
+}
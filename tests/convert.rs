@@ -0,0 +1,79 @@
+use buffman_tree::{
+    Trie,
+    branch::{BTreeBranch, ByteBranch, HashBranch},
+};
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeMap;
+
+#[test]
+fn convert() {
+    let trie: Trie<u8, u32> = Trie::from_iter([
+        (b"foo".to_vec(), 1),
+        (b"foobar".to_vec(), 2),
+        (b"bar".to_vec(), 3),
+    ]);
+    let mut mapped: Trie<u8, String, BTreeBranch<u8, String>> =
+        trie.map_values(|value| value.to_string());
+    assert_eq!(mapped.get(b"foobar"), Some(&"2".to_string()));
+    assert_eq!(mapped.insert(b"fo".iter().copied(), "4".into()), None);
+    assert_eq!(mapped.remove(b"foo"), Some("1".into()));
+
+    let mut bytes: Trie<u8, String, ByteBranch<String>> = mapped.convert_branch();
+    assert_eq!(
+        Vec::from_iter(bytes.iter_with_keys()),
+        [
+            (b"bar".to_vec(), &"3".to_string()),
+            (b"fo".to_vec(), &"4".to_string()),
+            (b"foobar".to_vec(), &"2".to_string())
+        ]
+    );
+    assert_eq!(bytes.insert(b"f".iter().copied(), "5".into()), None);
+    assert_eq!(bytes.get_deepest(b"foob"), Some(&"4".to_string()));
+
+    assert_eq!(
+        bytes
+            .try_map_values(|value| value.parse::<u8>().map_err(|_| value))
+            .map(|trie| Vec::from_iter(trie.into_iter())),
+        Ok(vec![3, 5, 4, 2])
+    );
+    let bad: Trie<u8, String> = Trie::from_iter([(b"x".to_vec(), "x".to_string())]);
+    assert_eq!(
+        bad.try_map_values(|value| value.parse::<u8>().map_err(|_| value))
+            .err(),
+        Some("x".to_string())
+    );
+}
+
+#[quickcheck]
+fn convert_oracle(pairs: Vec<(Vec<u8>, u16)>, more: Vec<(Vec<u8>, u16)>) {
+    let small = |bytes: &Vec<u8>| Vec::from_iter(bytes.iter().take(4).map(|b| b % 3));
+    let mut trie: Trie<u8, u16> = Trie::default();
+    let mut oracle = BTreeMap::new();
+    for (key, value) in &pairs {
+        let key = small(key);
+        trie.insert(key.clone(), *value);
+        oracle.insert(key, *value);
+    }
+    for (key, _) in pairs.iter().step_by(2) {
+        assert_eq!(trie.remove(&small(key)), oracle.remove(&small(key)));
+    }
+
+    let mut trie: Trie<u8, u32, HashBranch<u8, u32>> = trie
+        .map_values(u32::from)
+        .convert_branch::<HashBranch<u8, u32>>();
+    for (key, value) in &more {
+        let key = small(key);
+        assert_eq!(
+            trie.insert(key.clone(), u32::from(*value)),
+            oracle.insert(key, *value).map(u32::from)
+        );
+    }
+    for (key, value) in &oracle {
+        assert_eq!(trie.get(key), Some(&u32::from(*value)));
+    }
+    let trie: Trie<u8, u32, ByteBranch<u32>> = trie.convert_branch();
+    assert_eq!(
+        Vec::from_iter(trie.iter_with_keys().map(|(k, v)| (k, *v))),
+        Vec::from_iter(oracle.into_iter().map(|(k, v)| (k, u32::from(v))))
+    );
+}
@@ -0,0 +1,93 @@
+use buffman_tree::{
+    CowTrie, Trie,
+    branch::{ByteBranch, HashBranch},
+};
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeMap;
+
+#[test]
+fn snapshot() {
+    let mut trie: CowTrie<u8, u32> =
+        CowTrie::from_iter([(b"foo".to_vec(), 1), (b"bar".to_vec(), 2)]);
+    let before = trie.snapshot();
+    assert!(trie.ptr_eq(&before));
+    assert_eq!(trie.insert(b"foobar".iter().copied(), 3), None);
+    assert_eq!(trie.remove(b"bar"), Some(2));
+    assert!(!trie.ptr_eq(&before));
+    let export = Vec::from_iter(before.iter_with_keys());
+    assert_eq!(export, [(b"bar".to_vec(), &2), (b"foo".to_vec(), &1)]);
+    assert_eq!(
+        Vec::from_iter(trie.iter_with_keys()),
+        [(b"foo".to_vec(), &1), (b"foobar".to_vec(), &3)]
+    );
+    drop(before);
+    *trie.get_mut(b"foo").unwrap() = 4;
+    assert_eq!(trie.get_deepest(b"foob"), Some(&4));
+    assert_eq!(trie.len(), 2);
+
+    let hashed: CowTrie<u8, u32, HashBranch<u8, u32>> =
+        CowTrie::from_iter([(b"a".to_vec(), 1), (b"b".to_vec(), 2)]);
+    let mut copy = hashed.snapshot().to_trie();
+    copy.remove(b"a");
+    assert_eq!(hashed.get(b"a"), Some(&1));
+    assert_eq!(Vec::from_iter(copy.iter()), [&2]);
+
+    let mut slabs: Trie<u8, u32> = Trie::from_iter([(b"a".to_vec(), 1)]);
+    let before = slabs.snapshot();
+    slabs.insert(b"b".iter().copied(), 2);
+    assert_eq!(Vec::from_iter(before.iter()), [&1]);
+}
+
+#[test]
+fn shares_untouched_entries() {
+    let mut trie: CowTrie<u8, u32> = CowTrie::from_iter((0..1000u32).map(|i| (i.to_be_bytes(), i)));
+    let snapshot = trie.snapshot();
+    *trie.get_mut(&7u32.to_be_bytes()).unwrap() += 1;
+    trie.insert(1000u32.to_be_bytes(), 1000);
+    for i in 0..1000u32 {
+        let (old, new) = (snapshot.get(&i.to_be_bytes()), trie.get(&i.to_be_bytes()));
+        assert_eq!(old, Some(&i));
+        assert_eq!(std::ptr::eq(old.unwrap(), new.unwrap()), i != 7);
+    }
+}
+
+#[test]
+fn mutation_without_clone() {
+    #[derive(Debug, PartialEq)]
+    struct Unique(u32);
+    let mut trie: CowTrie<u8, Unique> = CowTrie::default();
+    assert_eq!(trie.insert(b"a".iter().copied(), Unique(1)), None);
+    assert_eq!(trie.insert(b"ab".iter().copied(), Unique(2)), None);
+    trie.get_mut(b"a").unwrap().0 += 10;
+    assert_eq!(trie.remove(b"ab"), Some(Unique(2)));
+    assert_eq!(Vec::from_iter(trie.iter()), [&Unique(11)]);
+}
+
+#[quickcheck]
+fn snapshot_oracle(ops: Vec<(u8, Vec<u8>, u8)>) {
+    let small = |bytes: Vec<u8>| Vec::from_iter(bytes.into_iter().take(4).map(|b| b % 3));
+    let mut trie: CowTrie<u8, u8, ByteBranch<u8>> = CowTrie::default();
+    let mut oracle = BTreeMap::new();
+    let mut snapshots = vec![];
+    for (op, key, value) in ops {
+        let key = small(key);
+        match op % 3 {
+            0 => assert_eq!(trie.insert(key.clone(), value), oracle.insert(key, value)),
+            1 => assert_eq!(trie.remove(&key), oracle.remove(&key)),
+            _ => snapshots.push((trie.snapshot(), oracle.clone())),
+        }
+    }
+    snapshots.push((trie.snapshot(), oracle));
+    for (snapshot, oracle) in snapshots {
+        assert_eq!(snapshot.len(), oracle.len());
+        assert_eq!(
+            Vec::from_iter(snapshot.iter_with_keys().map(|(k, v)| (k, *v))),
+            Vec::from_iter(oracle.clone())
+        );
+        let mut copy = snapshot.to_trie();
+        for key in oracle.keys() {
+            assert!(copy.remove(key).is_some());
+        }
+        assert!(copy.is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+use buffman_tree::domain::{DomainTrie, RuleError, RuleKind};
+
+fn lookup<'t, 'h>(
+    trie: &'t DomainTrie<usize>,
+    host: &'h str,
+) -> Option<(&'t str, &'h str, Option<&'h str>)> {
+    trie.lookup(host)
+        .map(|m| (m.rule, m.public_suffix, m.registrable_domain))
+}
+
+#[test]
+fn public_suffixes() {
+    let mut trie = DomainTrie::new();
+    for rule in [
+        "com",
+        "uk",
+        "co.uk",
+        "ck",
+        "*.ck",
+        "!www.ck",
+        "jp",
+        "*.kobe.jp",
+    ] {
+        assert_eq!(trie.insert(rule, rule.len()), Ok(None));
+    }
+    assert_eq!(trie.len(), 8);
+    assert_eq!(
+        lookup(&trie, "www.example.com"),
+        Some(("com", "com", Some("example.com")))
+    );
+    assert_eq!(lookup(&trie, "com"), Some(("com", "com", None)));
+    assert_eq!(
+        lookup(&trie, "a.b.example.co.uk."),
+        Some(("co.uk", "co.uk", Some("example.co.uk")))
+    );
+    assert_eq!(
+        lookup(&trie, "foo.bar.ck"),
+        Some(("*.ck", "bar.ck", Some("foo.bar.ck")))
+    );
+    assert_eq!(lookup(&trie, "bar.ck"), Some(("*.ck", "bar.ck", None)));
+    assert_eq!(lookup(&trie, "ck"), Some(("ck", "ck", None)));
+    assert_eq!(
+        lookup(&trie, "www.ck"),
+        Some(("!www.ck", "ck", Some("www.ck")))
+    );
+    assert_eq!(
+        lookup(&trie, "a.www.ck"),
+        Some(("!www.ck", "ck", Some("www.ck")))
+    );
+    assert_eq!(
+        lookup(&trie, "city.kobe.jp"),
+        Some(("*.kobe.jp", "city.kobe.jp", None))
+    );
+    assert_eq!(lookup(&trie, "example.org"), None);
+
+    let m = trie.lookup("x.y.ck").unwrap();
+    assert_eq!((m.kind, *m.value), (RuleKind::Wildcard, 4));
+    assert_eq!(
+        trie.registrable_domain("shop.example.co.uk"),
+        Some("example.co.uk")
+    );
+
+    assert_eq!(trie.remove("*.ck"), Ok(Some(4)));
+    assert_eq!(
+        lookup(&trie, "foo.bar.ck"),
+        Some(("ck", "ck", Some("bar.ck")))
+    );
+    assert_eq!(trie.remove("!www.ck"), Ok(Some(7)));
+    assert_eq!(trie.remove("!www.ck"), Ok(None));
+    assert_eq!(lookup(&trie, "www.ck"), Some(("ck", "ck", Some("www.ck"))));
+    assert_eq!(trie.len(), 6);
+}
+
+#[test]
+fn default_rule() {
+    let mut trie = DomainTrie::new();
+    for rule in ["*", "com", "*.ck"] {
+        assert_eq!(trie.insert(rule, rule.len()), Ok(None));
+    }
+    assert_eq!(
+        lookup(&trie, "www.example.org"),
+        Some(("*", "org", Some("example.org")))
+    );
+    assert_eq!(lookup(&trie, "org"), Some(("*", "org", None)));
+    assert_eq!(
+        lookup(&trie, "www.example.com"),
+        Some(("com", "com", Some("example.com")))
+    );
+    assert_eq!(
+        lookup(&trie, "foo.bar.ck"),
+        Some(("*.ck", "bar.ck", Some("foo.bar.ck")))
+    );
+    assert_eq!(Vec::from_iter(trie.rules().map(|(rule, _)| rule))[0], "*");
+    assert_eq!(trie.remove("*"), Ok(Some(1)));
+    assert_eq!(lookup(&trie, "example.org"), None);
+    assert_eq!(trie.len(), 2);
+}
+
+#[test]
+fn malformed_rules() {
+    let mut trie = DomainTrie::new();
+    assert_eq!(trie.insert("", ()), Err(RuleError::Empty));
+    assert_eq!(trie.insert("*.", ()), Err(RuleError::Empty));
+    assert!(matches!(
+        trie.insert("a..b", ()),
+        Err(RuleError::EmptyLabel { .. })
+    ));
+    assert!(matches!(
+        trie.insert("a.*.b", ()),
+        Err(RuleError::MisplacedWildcard { .. })
+    ));
+    assert!(matches!(
+        trie.insert("!*.b", ()),
+        Err(RuleError::MisplacedWildcard { .. })
+    ));
+    assert!(trie.is_empty());
+}
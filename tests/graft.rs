@@ -0,0 +1,98 @@
+use buffman_tree::{Trie, branch::ByteBranch};
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeMap;
+
+#[test]
+fn graft() {
+    let mut trie: Trie<u8, u32> = Trie::from_iter([
+        (b"eu/de".to_vec(), 1),
+        (b"eu/fr".to_vec(), 2),
+        (b"us/ca".to_vec(), 3),
+        (b"eu".to_vec(), 4),
+    ]);
+    let eu = trie.split_off_prefix(b"eu/");
+    assert_eq!(
+        Vec::from_iter(eu.iter_with_keys()),
+        [(b"de".to_vec(), &1), (b"fr".to_vec(), &2)]
+    );
+    assert_eq!(
+        Vec::from_iter(trie.iter_with_keys()),
+        [(b"eu".to_vec(), &4), (b"us/ca".to_vec(), &3)]
+    );
+    assert!(trie.split_off_prefix(b"asia").is_empty());
+
+    let mut us = trie.split_off_prefix(b"u");
+    assert_eq!(
+        Vec::from_iter(us.iter_with_keys()),
+        [(b"s/ca".to_vec(), &3)]
+    );
+    us.graft(b"s/".iter().copied(), eu);
+    assert_eq!(us.len(), 3);
+    assert_eq!(us.get(b"s/de"), Some(&1));
+
+    trie.graft_with(b"u".iter().copied(), us, |_, _| {
+        unreachable!("nothing below 'u' left")
+    });
+    let conflicting = Trie::from_iter([(b"ca".to_vec(), 10), (b"mx".to_vec(), 20)]);
+    trie.graft_with(b"us/".iter().copied(), conflicting, |old, new| *old += new);
+    assert_eq!(
+        Vec::from_iter(trie.iter_with_keys()),
+        [
+            (b"eu".to_vec(), &4),
+            (b"us/ca".to_vec(), &13),
+            (b"us/de".to_vec(), &1),
+            (b"us/fr".to_vec(), &2),
+            (b"us/mx".to_vec(), &20)
+        ]
+    );
+    let all = trie.split_off_prefix(b"");
+    assert!(trie.is_empty());
+    assert_eq!(all.len(), 5);
+}
+
+#[quickcheck]
+fn graft_oracle(pairs: Vec<(Vec<u8>, u8)>, prefix: Vec<u8>, other: Vec<u8>) {
+    let small = |bytes: &Vec<u8>| Vec::from_iter(bytes.iter().take(4).map(|b| b % 3));
+    let (prefix, other) = (small(&prefix), small(&other));
+    let mut trie: Trie<u8, u8, ByteBranch<u8>> = Trie::default();
+    let mut oracle = BTreeMap::new();
+    for (key, value) in &pairs {
+        trie.insert(small(key), *value);
+        oracle.insert(small(key), *value);
+    }
+
+    let split = trie.split_off_prefix(&prefix);
+    let (inside, mut outside): (BTreeMap<_, _>, BTreeMap<_, _>) = oracle
+        .into_iter()
+        .partition(|(key, _)| key.starts_with(&prefix));
+    let collect = |trie: &Trie<u8, u8, ByteBranch<u8>>| {
+        Vec::from_iter(trie.iter_with_keys().map(|(k, v)| (k, *v)))
+    };
+    assert_eq!(
+        collect(&split),
+        Vec::from_iter(
+            inside
+                .iter()
+                .map(|(key, value)| (key[prefix.len()..].to_vec(), *value))
+        )
+    );
+    assert_eq!(collect(&trie), Vec::from_iter(outside.clone()));
+    assert_eq!(trie.len(), outside.len());
+
+    trie.graft_with(other.clone(), split, |old, new| {
+        *old = old.wrapping_add(new)
+    });
+    for (key, value) in inside {
+        let key = [&other[..], &key[prefix.len()..]].concat();
+        outside
+            .entry(key)
+            .and_modify(|old: &mut u8| *old = old.wrapping_add(value))
+            .or_insert(value);
+    }
+    assert_eq!(collect(&trie), Vec::from_iter(outside.clone()));
+    assert_eq!(trie.len(), outside.len());
+    for key in outside.keys() {
+        assert!(trie.remove(key).is_some());
+    }
+    assert!(trie.is_empty());
+}
@@ -0,0 +1,114 @@
+use buffman_tree::ip::{IpNet, IpNetError, IpPrefixTable};
+use quickcheck_macros::quickcheck;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+fn net(s: &str) -> IpNet {
+    s.parse().unwrap()
+}
+
+#[test]
+fn ip_net() {
+    assert_eq!(net("10.1.2.3/8"), net("10.0.0.0/8"));
+    assert_eq!(net("10.1.2.3/8").to_string(), "10.0.0.0/8");
+    assert_eq!(net("::1/0").to_string(), "::/0");
+    assert_eq!(
+        "10.0.0.0/33".parse::<IpNet>(),
+        Err(IpNetError::TooLong { len: 33, max: 32 })
+    );
+    assert_eq!("10.0.0.0".parse::<IpNet>(), Err(IpNetError::MissingLen));
+    assert!(net("10.0.0.0/8").covers(&net("10.128.0.0/9")));
+    assert!(!net("10.0.0.0/8").covers(&net("0.0.0.0/0")));
+    assert!(!net("0.0.0.0/0").covers(&net("::/0")));
+    assert_eq!(
+        net("10.0.0.0/8").children(),
+        Some((net("10.0.0.0/9"), net("10.128.0.0/9")))
+    );
+    assert_eq!(net("10.128.0.0/9").parent(), Some(net("10.0.0.0/8")));
+}
+
+#[test]
+fn routing() {
+    let mut table = IpPrefixTable::from_iter([
+        (net("0.0.0.0/0"), "default"),
+        (net("10.0.0.0/8"), "private"),
+        (net("10.20.0.0/14"), "site"),
+        (net("10.20.30.40/32"), "host"),
+        (net("2001:db8::/32"), "doc"),
+    ]);
+    assert_eq!(table.len(), 5);
+    let addr = Ipv4Addr::new(10, 21, 0, 1);
+    assert_eq!(
+        table.longest_match(addr),
+        Some((net("10.20.0.0/14"), &"site"))
+    );
+    assert_eq!(
+        Vec::from_iter(table.all_matches(addr).map(|(_, v)| *v)),
+        ["default", "private", "site"]
+    );
+    assert_eq!(
+        table.longest_match(Ipv4Addr::new(192, 168, 0, 1)),
+        Some((net("0.0.0.0/0"), &"default"))
+    );
+    assert_eq!(table.longest_match(Ipv6Addr::LOCALHOST), None);
+    assert_eq!(
+        table.longest_match("2001:db8::1".parse::<IpAddr>().unwrap()),
+        Some((net("2001:db8::/32"), &"doc"))
+    );
+    assert_eq!(
+        Vec::from_iter(table.covered_by(&net("10.16.0.0/12")).map(|(n, _)| n)),
+        [net("10.20.0.0/14"), net("10.20.30.40/32")]
+    );
+    assert!(table.is_covered(&net("172.16.0.0/12")));
+
+    assert_eq!(table.remove(&net("10.20.0.0/14")), Some("site"));
+    assert_eq!(table.remove(&net("10.20.0.0/14")), None);
+    assert_eq!(
+        table.longest_match(addr),
+        Some((net("10.0.0.0/8"), &"private"))
+    );
+    table.remove(&net("0.0.0.0/0"));
+    assert!(!table.is_covered(&net("172.16.0.0/12")));
+}
+
+#[test]
+fn aggregation() {
+    let mut table = IpPrefixTable::from_iter([
+        (net("10.0.0.0/26"), 1),
+        (net("10.0.0.64/26"), 1),
+        (net("10.0.0.128/26"), 1),
+        (net("10.0.0.192/26"), 1),
+        (net("10.0.1.0/24"), 2),
+    ]);
+    assert!(table.can_aggregate(&net("10.0.0.0/25")));
+    assert!(!table.can_aggregate(&net("10.0.0.0/24")));
+    assert!(!table.can_aggregate(&net("10.0.0.0/23")));
+    assert_eq!(table.aggregate(), 3);
+    assert_eq!(
+        Vec::from_iter(table.iter().map(|(n, v)| (n, *v))),
+        [(net("10.0.0.0/24"), 1), (net("10.0.1.0/24"), 2)]
+    );
+}
+
+#[quickcheck]
+fn longest_match_oracle(entries: Vec<(u32, u8)>, searches: Vec<u32>) {
+    let nets = Vec::from_iter(
+        entries
+            .into_iter()
+            .map(|(addr, len)| IpNet::new(Ipv4Addr::from_bits(addr), len % 33).unwrap()),
+    );
+    let table = IpPrefixTable::from_iter(nets.iter().map(|net| (*net, *net)));
+    for addr in searches.into_iter().map(Ipv4Addr::from_bits) {
+        let expected = nets
+            .iter()
+            .filter(|net| net.contains(addr))
+            .max_by_key(|net| net.prefix_len());
+        assert_eq!(table.longest_match(addr).map(|(_, v)| v), expected);
+        assert_eq!(
+            table.all_matches(addr).count(),
+            nets.iter()
+                .filter(|net| net.contains(addr))
+                .collect::<std::collections::BTreeSet<_>>()
+                .len()
+        );
+    }
+}
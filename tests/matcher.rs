@@ -0,0 +1,132 @@
+use buffman_tree::{MatchKind, Trie, branch::ByteBranch};
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeSet;
+
+fn trie(keys: &[&str]) -> Trie<u8, String, ByteBranch<String>> {
+    let mut trie = Trie::default();
+    for key in keys {
+        trie.insert(key.bytes(), key.to_string());
+    }
+    trie
+}
+
+fn find(
+    trie: &Trie<u8, String, ByteBranch<String>>,
+    kind: MatchKind,
+    haystack: &str,
+) -> Vec<(usize, usize, String)> {
+    let matcher = trie.build_matcher().match_kind(kind);
+    Vec::from_iter(
+        matcher
+            .find_iter(haystack.as_bytes())
+            .map(|(start, end, value)| (start, end, value.clone())),
+    )
+}
+
+#[test]
+fn overlapping() {
+    let trie = trie(&["he", "she", "his", "hers", "s", ""]);
+    assert_eq!(
+        find(&trie, MatchKind::Overlapping, "ushers"),
+        [
+            (1, 2, "s".into()),
+            (1, 4, "she".into()),
+            (2, 4, "he".into()),
+            (2, 6, "hers".into()),
+            (5, 6, "s".into()),
+        ]
+    );
+    assert_eq!(find(&trie, MatchKind::Overlapping, "xyz"), []);
+    assert_eq!(find(&Trie::default(), MatchKind::Overlapping, "abc"), []);
+}
+
+#[test]
+fn leftmost_longest() {
+    let trie = trie(&["abcd", "bc", "abc", "b", "cde", "e"]);
+    assert_eq!(
+        find(&trie, MatchKind::LeftmostLongest, "abcde"),
+        [(0, 4, "abcd".into()), (4, 5, "e".into())]
+    );
+    assert_eq!(
+        find(&trie, MatchKind::LeftmostLongest, "xabcxbcde"),
+        [
+            (1, 4, "abc".into()),
+            (5, 7, "bc".into()),
+            (8, 9, "e".into()),
+        ]
+    );
+}
+
+#[test]
+fn compressed_prefixes() {
+    // long single-child runs end up in node prefixes, failure links must point into them
+    let trie = trie(&["abcabcabd", "cabd", "bcab"]);
+    assert_eq!(
+        find(&trie, MatchKind::Overlapping, "abcabcabcabd"),
+        [
+            (1, 5, "bcab".into()),
+            (4, 8, "bcab".into()),
+            (7, 11, "bcab".into()),
+            (3, 12, "abcabcabd".into()),
+            (8, 12, "cabd".into()),
+        ]
+    );
+}
+
+fn brute_force(keys: &BTreeSet<Vec<u8>>, haystack: &[u8]) -> BTreeSet<(usize, usize)> {
+    let mut matches = BTreeSet::new();
+    for start in 0..haystack.len() {
+        for end in start + 1..=haystack.len() {
+            if keys.contains(&haystack[start..end]) {
+                matches.insert((start, end));
+            }
+        }
+    }
+    matches
+}
+
+fn small(bytes: Vec<u8>) -> Vec<u8> {
+    bytes.into_iter().map(|b| b % 3).collect()
+}
+
+#[quickcheck]
+fn overlapping_oracle(keys: Vec<Vec<u8>>, haystack: Vec<u8>) {
+    let keys = BTreeSet::from_iter(keys.into_iter().map(small));
+    let haystack = small(haystack);
+    let mut trie = Trie::<u8, Vec<u8>, ByteBranch<_>>::default();
+    for key in &keys {
+        trie.insert(key.iter().copied(), key.clone());
+    }
+    let matcher = trie.build_matcher();
+    let found = Vec::from_iter(matcher.find_iter(&haystack).map(|(start, end, key)| {
+        assert_eq!(&haystack[start..end], key);
+        (start, end)
+    }));
+    assert!(found.is_sorted_by_key(|(start, end)| (*end, *start)));
+    assert_eq!(BTreeSet::from_iter(found), brute_force(&keys, &haystack));
+}
+
+#[quickcheck]
+fn leftmost_longest_oracle(keys: Vec<Vec<u8>>, haystack: Vec<u8>) {
+    let keys = BTreeSet::from_iter(keys.into_iter().map(small));
+    let haystack = small(haystack);
+    let mut trie = Trie::<u8, Vec<u8>, ByteBranch<_>>::default();
+    for key in &keys {
+        trie.insert(key.iter().copied(), key.clone());
+    }
+    let all = brute_force(&keys, &haystack);
+    let mut expected = vec![];
+    let mut pos = 0;
+    while let Some(&(start, _)) = all.range((pos, 0)..).next() {
+        let end = all.range((start, 0)..(start + 1, 0)).last().unwrap().1;
+        expected.push((start, end));
+        pos = end;
+    }
+    let matcher = trie.build_matcher().match_kind(MatchKind::LeftmostLongest);
+    let found = Vec::from_iter(
+        matcher
+            .find_iter(&haystack)
+            .map(|(start, end, _)| (start, end)),
+    );
+    assert_eq!(found, expected);
+}
@@ -0,0 +1,88 @@
+use buffman_tree::{MoveError, Trie};
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeMap;
+
+#[test]
+fn move_prefix() {
+    let mut trie: Trie<u8, u32> = Trie::from_iter([
+        (b"a/b/c".to_vec(), 1),
+        (b"a/b/d".to_vec(), 2),
+        (b"a/b".to_vec(), 3),
+        (b"a/e".to_vec(), 4),
+    ]);
+    let bytes = |s: &'static str| s.bytes();
+    assert_eq!(trie.move_prefix(bytes("a/b/"), bytes("x/y/")), Ok(()));
+    assert_eq!(
+        Vec::from_iter(trie.iter_with_keys()),
+        [
+            (b"a/b".to_vec(), &3),
+            (b"a/e".to_vec(), &4),
+            (b"x/y/c".to_vec(), &1),
+            (b"x/y/d".to_vec(), &2)
+        ]
+    );
+    assert_eq!(
+        trie.move_prefix(bytes("a/b/"), bytes("z")),
+        Err(MoveError::NotFound)
+    );
+    assert_eq!(
+        trie.move_prefix(bytes("x/"), bytes("a/")),
+        Err(MoveError::Occupied)
+    );
+    assert_eq!(trie.get(b"x/y/c"), Some(&1));
+    assert_eq!(trie.move_prefix(bytes("a"), bytes("a/a")), Ok(()));
+    assert_eq!(
+        trie.move_prefix(bytes("a/a/"), bytes("")),
+        Err(MoveError::Occupied)
+    );
+    assert_eq!(trie.move_prefix(bytes(""), bytes("r/")), Ok(()));
+    assert_eq!(
+        Vec::from_iter(trie.keys()),
+        [
+            b"r/a/a/b".to_vec(),
+            b"r/a/a/e".to_vec(),
+            b"r/x/y/c".to_vec(),
+            b"r/x/y/d".to_vec()
+        ]
+    );
+}
+
+#[quickcheck]
+fn move_prefix_oracle(pairs: Vec<(Vec<u8>, u8)>, moves: Vec<(Vec<u8>, Vec<u8>)>) {
+    let small = |bytes: &Vec<u8>| Vec::from_iter(bytes.iter().take(4).map(|b| b % 3));
+    let mut trie: Trie<u8, u8> = Trie::default();
+    let mut oracle = BTreeMap::new();
+    for (key, value) in &pairs {
+        trie.insert(small(key), *value);
+        oracle.insert(small(key), *value);
+    }
+    for (from, to) in &moves {
+        let (from, to) = (small(from), small(to));
+        let (moved, kept): (BTreeMap<_, _>, BTreeMap<_, _>) = oracle
+            .clone()
+            .into_iter()
+            .partition(|(key, _)| key.starts_with(&from));
+        let expected = if moved.is_empty() {
+            Err(MoveError::NotFound)
+        } else if kept.keys().any(|key| key.starts_with(&to)) {
+            Err(MoveError::Occupied)
+        } else {
+            oracle = kept;
+            oracle.extend(
+                moved
+                    .into_iter()
+                    .map(|(key, value)| ([&to[..], &key[from.len()..]].concat(), value)),
+            );
+            Ok(())
+        };
+        assert_eq!(trie.move_prefix(from, to), expected);
+        assert_eq!(
+            Vec::from_iter(trie.iter_with_keys().map(|(k, v)| (k, *v))),
+            Vec::from_iter(oracle.clone())
+        );
+    }
+    for key in oracle.keys() {
+        assert!(trie.remove(key).is_some());
+    }
+    assert!(trie.is_empty());
+}
@@ -0,0 +1,68 @@
+use buffman_tree::{Trie, TrieMultiMap};
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeMap;
+
+#[test]
+fn tags() {
+    let mut tags: TrieMultiMap<u8, &str> = TrieMultiMap::from_iter([
+        (b"rust".to_vec(), "a"),
+        (b"rust".to_vec(), "b"),
+        (b"ruby".to_vec(), "c"),
+        (b"rust".to_vec(), "a"),
+    ]);
+    assert_eq!(tags.len(), 4);
+    assert_eq!(tags.keys_len(), 2);
+    assert_eq!(Vec::from_iter(tags.get_all(b"rust")), [&"a", &"b", &"a"]);
+    assert_eq!(tags.get_all(b"ru").count(), 0);
+
+    assert_eq!(tags.remove_one(b"rust", &"a"), Some("a"));
+    assert_eq!(tags.remove_one(b"rust", &"x"), None);
+    assert_eq!(Vec::from_iter(tags.get_all(b"rust")), [&"b", &"a"]);
+    assert_eq!(tags.remove_one(b"ruby", &"c"), Some("c"));
+    assert!(!tags.contains_key(b"ruby"));
+    assert_eq!(tags.keys_len(), 1);
+
+    assert_eq!(tags.remove_all(b"rust"), ["b", "a"]);
+    assert_eq!(tags.remove_all(b"rust"), Vec::<&str>::new());
+    assert!(tags.is_empty());
+    assert_eq!(tags.as_trie(), &Trie::default());
+}
+
+#[quickcheck]
+fn multimap_oracle(ops: Vec<(u8, Vec<u8>, u8)>) {
+    let small = |bytes: Vec<u8>| Vec::from_iter(bytes.into_iter().take(3).map(|b| b % 3));
+    let mut multimap: TrieMultiMap<u8, u8> = TrieMultiMap::new();
+    let mut oracle = BTreeMap::<Vec<u8>, Vec<u8>>::new();
+    for (op, key, value) in ops {
+        let (key, value) = (small(key), value % 4);
+        match op % 3 {
+            0 => {
+                multimap.insert(key.clone(), value);
+                oracle.entry(key).or_default().push(value);
+            }
+            1 => {
+                let expected = oracle.get_mut(&key).and_then(|values| {
+                    let i = values.iter().position(|v| *v == value)?;
+                    Some(values.remove(i))
+                });
+                oracle.retain(|_, values| !values.is_empty());
+                assert_eq!(multimap.remove_one(&key, &value), expected);
+            }
+            _ => {
+                let expected = oracle.remove(&key).unwrap_or_default();
+                assert_eq!(multimap.remove_all(&key), expected);
+            }
+        }
+        assert_eq!(multimap.len(), oracle.values().map(Vec::len).sum::<usize>());
+        assert_eq!(multimap.keys_len(), oracle.len());
+    }
+    for (key, values) in &oracle {
+        assert_eq!(Vec::from_iter(multimap.get_all(key).copied()), *values);
+    }
+    for (key, values) in oracle {
+        for value in values {
+            assert_eq!(multimap.remove_one(&key, &value), Some(value));
+        }
+    }
+    assert_eq!(multimap.as_trie(), &Trie::default());
+}
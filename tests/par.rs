@@ -0,0 +1,60 @@
+#![cfg(feature = "rayon")]
+
+use buffman_tree::{Trie, branch::ByteBranch};
+use quickcheck_macros::quickcheck;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+
+#[test]
+fn par() {
+    let mut trie: Trie<u8, u32> = (0..10_000u32)
+        .into_par_iter()
+        .map(|i| (i.to_string().into_bytes(), i))
+        .collect();
+    assert_eq!(trie.len(), 10_000);
+    assert_eq!(trie.get(b"1234"), Some(&1234));
+    assert_eq!(trie.par_iter().map(|&i| i as u64).sum::<u64>(), 49_995_000);
+
+    let len = trie.len();
+    assert_eq!(trie.par_iter_mut().count(), len);
+    trie.par_iter_mut().for_each(|i| *i *= 2);
+    assert_eq!(trie.get(b"1234"), Some(&2468));
+
+    trie.par_extend(
+        (5_000..15_000u32)
+            .into_par_iter()
+            .map(|i| (i.to_string().into_bytes(), i)),
+    );
+    assert_eq!(trie.len(), 15_000);
+    assert_eq!(trie.get(b"1234"), Some(&2468));
+    assert_eq!(trie.get(b"5000"), Some(&5000));
+    assert_eq!(trie.get(b"14999"), Some(&14999));
+}
+
+#[quickcheck]
+fn par_oracle(pairs: Vec<(Vec<u8>, u8)>, more: Vec<(Vec<u8>, u8)>) {
+    let small = |bytes: &Vec<u8>| Vec::from_iter(bytes.iter().take(4).map(|b| b % 3));
+    let mut oracle = BTreeMap::new();
+    for (key, value) in pairs.iter().chain(&more) {
+        oracle.insert(small(key), *value);
+    }
+    let mut trie: Trie<u8, u8, ByteBranch<u8>> = pairs
+        .par_iter()
+        .map(|(key, value)| (small(key), *value))
+        .collect();
+    trie.par_extend(more.par_iter().map(|(key, value)| (small(key), *value)));
+    assert_eq!(
+        Vec::from_iter(trie.iter_with_keys().map(|(k, v)| (k, *v))),
+        Vec::from_iter(oracle.clone())
+    );
+    let mut values = trie.par_iter().copied().collect::<Vec<_>>();
+    values.sort();
+    let mut expected = Vec::from_iter(oracle.values().copied());
+    expected.sort();
+    assert_eq!(values, expected);
+    trie.par_iter_mut()
+        .for_each(|value| *value = value.wrapping_add(1));
+    for (key, value) in &oracle {
+        assert_eq!(trie.get(key), Some(&value.wrapping_add(1)));
+    }
+}
@@ -0,0 +1,54 @@
+use buffman_tree::{branch::HashBranch, path::PathTrie};
+use std::{collections::BTreeSet, path::Path};
+
+#[test]
+fn path_trie() {
+    let mut trie: PathTrie<usize> = PathTrie::from_iter([
+        ("/usr/bin", 0),
+        ("/usr/lib/", 1),
+        ("/usr/lib/rustlib", 2),
+        ("/etc", 3),
+    ]);
+    assert_eq!(trie.get("usr//bin"), Some(&0));
+    assert_eq!(trie.get(Path::new("/usr/lib")), Some(&1));
+    assert_eq!(trie.get("/usr"), None);
+    assert_eq!(trie.get_deepest("/usr/lib/rustlib/src"), Some(&2));
+    assert_eq!(trie.get_deepest(Path::new("/usr/./lib/x")), Some(&1));
+    assert_eq!(trie.get_deepest("/home"), None);
+    assert_eq!(Vec::from_iter(trie.children("/")), ["etc", "usr"]);
+    assert_eq!(Vec::from_iter(trie.children("/usr")), ["bin", "lib"]);
+    assert_eq!(Vec::from_iter(trie.children("/usr/lib")), ["rustlib"]);
+    assert_eq!(trie.children("/usr/lib/rustlib").count(), 0);
+    assert_eq!(trie.children("/home").count(), 0);
+    assert_eq!(
+        Vec::from_iter(trie.descendants("/usr/lib")),
+        [("".to_owned(), &1), ("rustlib".to_owned(), &2)]
+    );
+    assert_eq!(
+        Vec::from_iter(trie.iter().map(|(k, _)| k)),
+        ["etc", "usr/bin", "usr/lib", "usr/lib/rustlib"]
+    );
+
+    assert_eq!(trie.remove("/usr/bin"), Some(0));
+    // single remaining child got contracted into a compressed prefix
+    assert_eq!(Vec::from_iter(trie.children("/usr")), ["lib"]);
+    assert_eq!(trie.len(), 3);
+}
+
+#[test]
+fn separator() {
+    let trie: PathTrie<&str, HashBranch<_, _>> = {
+        let mut trie = PathTrie::with_separator('.');
+        trie.insert("com.example", "example");
+        trie.insert("com.example.www", "www");
+        trie.insert("org.rust-lang", "rust");
+        trie
+    };
+    assert_eq!(trie.get("com.example.www"), Some(&"www"));
+    assert_eq!(trie.get("com/example"), None);
+    assert_eq!(
+        BTreeSet::from_iter(trie.children("")),
+        BTreeSet::from(["com", "org"])
+    );
+    assert_eq!(trie.get_deepest("com.example.mail"), Some(&"example"));
+}
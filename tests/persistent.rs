@@ -0,0 +1,97 @@
+use buffman_tree::{PersistentTrie, Trie, branch::ByteBranch};
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeMap;
+
+#[test]
+fn versions() {
+    let v0: PersistentTrie<u8, &str> = PersistentTrie::new();
+    let v1 = v0.insert(b"foo".iter().copied(), "foo");
+    let v2 = v1.insert(b"foobar".iter().copied(), "foobar");
+    let v3 = v2.insert(b"foo".iter().copied(), "FOO");
+    let v4 = v3.remove(b"foobar");
+    assert!(v4.remove(b"bar").ptr_eq(&v4));
+
+    assert!(v0.is_empty());
+    assert_eq!(v1.get(b"foo"), Some(&"foo"));
+    assert_eq!(v2.get_deepest(b"foobaz"), Some(&"foo"));
+    assert_eq!(v2.get_deepest(b"foobarbaz"), Some(&"foobar"));
+    assert_eq!(Vec::from_iter(v3.iter()), [&"FOO", &"foobar"]);
+    assert_eq!(
+        Vec::from_iter(v4.iter_with_keys()),
+        [(b"foo".to_vec(), &"FOO")]
+    );
+    assert_eq!(
+        [v0.len(), v1.len(), v2.len(), v3.len(), v4.len()],
+        [0, 1, 2, 2, 1]
+    );
+    assert_eq!(v4.remove(b"foo"), v0);
+}
+
+#[test]
+fn queries() {
+    let v0: PersistentTrie<u8, u32, ByteBranch<u32>> =
+        PersistentTrie::from_iter([(b"/".to_vec(), 0), (b"/api".to_vec(), 1)]);
+    let v1 = v0.insert(b"/api/users".iter().copied(), 2);
+    assert_eq!(v0.try_get(b"/api/users"), Ok(&1));
+    assert_eq!(v1.try_get_exact(b"/api/us"), Err(Some(&1)));
+    assert_eq!(v1.get_prefixes(b"/api/users/1"), [&0, &1, &2]);
+    let api = v1.subtrie(b"/api").unwrap();
+    assert_eq!(api.value(), Some(&1));
+    assert_eq!(
+        Vec::from_iter(api.keys()),
+        [b"".to_vec(), b"/users".to_vec()]
+    );
+    assert!(v0.subtrie(b"/api/").is_none());
+    // entries off the updated path stay shared
+    assert!(std::ptr::eq(v0.get(b"/").unwrap(), v1.get(b"/").unwrap()));
+}
+
+#[test]
+fn deep_keys() {
+    const DEPTH: usize = 100_000;
+    let v0: PersistentTrie<u8, usize> = PersistentTrie::new();
+    let v1 = v0.insert(vec![0; DEPTH], DEPTH);
+    let v2 = v1.insert(vec![0; DEPTH / 2], DEPTH / 2);
+    let v3 = v2.remove(&vec![0; DEPTH]);
+    assert_eq!(v2.get_deepest(&vec![0; DEPTH + 1]), Some(&DEPTH));
+    assert_eq!(v3.get_deepest(&vec![0; DEPTH + 1]), Some(&(DEPTH / 2)));
+    drop((v0, v1, v2));
+    assert_eq!(v3.len(), 1);
+}
+
+#[quickcheck]
+fn persistent_oracle(ops: Vec<(bool, Vec<u8>, u8)>) {
+    let small = |bytes: Vec<u8>| Vec::from_iter(bytes.into_iter().take(4).map(|b| b % 3));
+    let mut versions = vec![(PersistentTrie::new(), BTreeMap::new())];
+    for (insert, key, value) in ops {
+        let key = small(key);
+        let (trie, oracle): &(PersistentTrie<u8, u8>, BTreeMap<Vec<u8>, u8>) =
+            versions.last().unwrap();
+        let (mut trie, mut oracle) = (trie.clone(), oracle.clone());
+        if insert {
+            trie = trie.insert(key.clone(), value);
+            oracle.insert(key, value);
+        } else {
+            trie = trie.remove(&key);
+            oracle.remove(&key);
+        }
+        versions.push((trie, oracle));
+    }
+    for (trie, oracle) in &versions {
+        assert_eq!(trie.len(), oracle.len());
+        assert_eq!(
+            Vec::from_iter(trie.iter_with_keys().map(|(k, v)| (k, *v))),
+            Vec::from_iter(oracle.clone())
+        );
+        let reference: Trie<u8, u8> = Trie::from_iter(oracle.clone());
+        for key in oracle.keys() {
+            for len in 0..=key.len() {
+                assert_eq!(trie.get(&key[..len]), reference.get(&key[..len]));
+                assert_eq!(
+                    trie.get_deepest(&key[..len]),
+                    reference.get_deepest(&key[..len])
+                );
+            }
+        }
+    }
+}
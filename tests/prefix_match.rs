@@ -0,0 +1,83 @@
+use buffman_tree::{Match, Trie};
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeMap;
+
+#[test]
+fn with_len() {
+    let trie: Trie<u8, (Vec<u8>, usize)> = Trie::from_iter([
+        (b"a".to_vec(), 0),
+        (b"abcd".to_vec(), 1),
+        (b"abcdef".to_vec(), 2),
+    ]);
+    let deepest = |key: &[u8]| {
+        trie.get_deepest_with_len(key)
+            .map(|found| found.map(|(_, v)| *v))
+    };
+    assert_eq!(deepest(b""), None);
+    assert_eq!(
+        deepest(b"abc"),
+        Some(Match {
+            depth: 1,
+            value: 0,
+            exact: false
+        })
+    );
+    assert_eq!(
+        deepest(b"abcd"),
+        Some(Match {
+            depth: 4,
+            value: 1,
+            exact: true
+        })
+    );
+    assert_eq!(
+        deepest(b"abcdxyz"),
+        Some(Match {
+            depth: 4,
+            value: 1,
+            exact: false
+        })
+    );
+    assert_eq!(
+        deepest(b"abcdefg"),
+        Some(Match {
+            depth: 6,
+            value: 2,
+            exact: false
+        })
+    );
+
+    // 'try_get' keeps returning the deepest value inside 'Ok', as it always has.
+    assert_eq!(trie.try_get(b"abcd").map(|(_, v)| *v), Ok(1));
+    assert_eq!(trie.try_get(b"abcde").map(|(_, v)| *v), Ok(1));
+    assert_eq!(trie.try_get(b"b"), Err(None));
+    assert_eq!(
+        trie.try_get_with_len(b"abcde")
+            .map(|found| (found.depth, found.exact)),
+        Ok((4, false))
+    );
+    assert_eq!(trie.try_get_exact(b"abcd").map(|(_, v)| *v), Ok(1));
+    assert_eq!(
+        trie.try_get_exact(b"abcde")
+            .map_err(|deepest| deepest.map(|(_, v)| *v)),
+        Err(Some(1))
+    );
+    assert_eq!(trie.try_get_exact(b"b"), Err(None));
+}
+
+#[quickcheck]
+fn with_len_oracle(keys: Vec<Vec<u8>>, searches: Vec<Vec<u8>>) {
+    let small = |bytes: Vec<u8>| Vec::from_iter(bytes.into_iter().map(|b| b % 4));
+    let keys = BTreeMap::from_iter(keys.into_iter().map(small).map(|key| (key, ())));
+    let trie: Trie<u8, (Vec<u8>, ())> = Trie::from_iter(keys.clone());
+    for search in searches.into_iter().map(small) {
+        let expected = (0..=search.len())
+            .rev()
+            .find(|len| keys.contains_key(&search[..*len]))
+            .map(|len| (len, search[..len].to_vec(), len == search.len()));
+        let found = trie
+            .get_deepest_with_len(&search)
+            .map(|found| (found.depth, found.value.0.clone(), found.exact));
+        assert_eq!(found, expected);
+    }
+}
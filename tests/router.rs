@@ -0,0 +1,84 @@
+use buffman_tree::router::{RouteError, Router};
+
+#[test]
+fn matching() {
+    let mut router = Router::new();
+    for (template, value) in [
+        ("/", 0),
+        ("/users", 1),
+        ("/users/me", 2),
+        ("/users/:id", 3),
+        ("/users/:id/posts/*rest", 4),
+        ("/users/:id/posts/latest", 5),
+        ("/static/*file", 6),
+        ("/:lang/about", 7),
+        ("/docs/intro", 8),
+    ] {
+        router.insert(template, value).unwrap();
+    }
+    let matched = |path| {
+        router
+            .match_path(path)
+            .map(|m| (*m.value, m.params.clone()))
+    };
+    assert_eq!(matched("/"), Some((0, vec![])));
+    assert_eq!(matched("/users/"), Some((1, vec![])));
+    assert_eq!(matched("/users/me"), Some((2, vec![])));
+    assert_eq!(matched("/users/42"), Some((3, vec![("id", "42")])));
+    assert_eq!(
+        matched("/users/42/posts/a/b"),
+        Some((4, vec![("id", "42"), ("rest", "a/b")]))
+    );
+    assert_eq!(
+        matched("/users/me/posts/latest"),
+        Some((5, vec![("id", "me")]))
+    );
+    assert_eq!(matched("/users/42/posts"), None);
+    assert_eq!(
+        matched("/static/css/site.css"),
+        Some((6, vec![("file", "css/site.css")]))
+    );
+    assert_eq!(matched("/users/about"), Some((3, vec![("id", "about")])));
+    // static 'docs' is tried first, but backtracks into ':lang'
+    assert_eq!(matched("/docs/intro"), Some((8, vec![])));
+    assert_eq!(matched("/docs/about"), Some((7, vec![("lang", "docs")])));
+    assert_eq!(matched("/en/about"), Some((7, vec![("lang", "en")])));
+    assert_eq!(matched("/en/contact"), None);
+
+    let m = router.match_path("/users/7/posts/x").unwrap();
+    assert_eq!(m.template, "/users/:id/posts/*rest");
+    assert_eq!(m.param("rest"), Some("x"));
+    assert_eq!(m.param("missing"), None);
+}
+
+#[test]
+fn conflicts() {
+    let mut router = Router::new();
+    router.insert("/users/:id", ()).unwrap();
+    assert_eq!(
+        router.insert("/users/:name/", ()),
+        Err(RouteError::Conflict {
+            template: "/users/:name/".to_owned(),
+            existing: "/users/:id".to_owned()
+        })
+    );
+    assert_eq!(
+        router.insert("/files/*path/edit", ()),
+        Err(RouteError::CatchAllNotLast {
+            template: "/files/*path/edit".to_owned()
+        })
+    );
+    assert_eq!(
+        router.insert("/a/:/b", ()),
+        Err(RouteError::EmptyName {
+            template: "/a/:/b".to_owned()
+        })
+    );
+    assert!(matches!(
+        router.insert("/:x/:x", ()),
+        Err(RouteError::DuplicateName { .. })
+    ));
+    assert_eq!(router.len(), 1);
+    assert_eq!(router.remove("/users/:other"), Ok(Some(())));
+    assert!(router.is_empty());
+}
@@ -0,0 +1,78 @@
+use buffman_tree::{TrieSet, branch::ByteBranch};
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeSet;
+
+#[test]
+fn set() {
+    let mut set: TrieSet<u8> =
+        TrieSet::from_iter([&b"foo"[..], b"foobar", b"bar"].map(|k| k.iter().copied()));
+    assert_eq!(set.len(), 3);
+    assert!(!set.insert(b"foo".iter().copied()));
+    assert!(set.insert(b"fo".iter().copied()));
+    assert!(set.contains(b"foo"));
+    assert!(!set.contains(b"foob"));
+    assert!(set.contains_prefix_of(b"foob"));
+    assert!(set.contains_prefix_of(b"barbaz"));
+    assert!(!set.contains_prefix_of(b"f"));
+    assert_eq!(
+        Vec::from_iter(set.iter()),
+        [
+            b"bar".to_vec(),
+            b"fo".to_vec(),
+            b"foo".to_vec(),
+            b"foobar".to_vec()
+        ]
+    );
+
+    assert!(set.remove(b"foo"));
+    assert!(!set.remove(b"foo"));
+    assert!(set.contains(b"foobar"));
+    assert_eq!(set.len(), 3);
+    set.clear();
+    assert!(set.is_empty());
+    assert_eq!(set.iter().count(), 0);
+}
+
+#[quickcheck]
+fn set_oracle(a: Vec<(bool, Vec<u8>)>, b: BTreeSet<Vec<u8>>) {
+    let small = |bytes: &Vec<u8>| Vec::from_iter(bytes.iter().take(4).map(|b| b % 3));
+    let mut set: TrieSet<u8, ByteBranch<()>> = TrieSet::new();
+    let mut oracle = BTreeSet::new();
+    for (insert, key) in &a {
+        let key = small(key);
+        if *insert {
+            assert_eq!(set.insert(key.clone()), oracle.insert(key));
+        } else {
+            assert_eq!(set.remove(&key), oracle.remove(&key));
+        }
+        assert_eq!(set.len(), oracle.len());
+    }
+    assert_eq!(
+        Vec::from_iter(set.iter()),
+        Vec::from_iter(oracle.iter().cloned())
+    );
+    for (_, key) in &a {
+        let key = small(key);
+        assert_eq!(
+            set.contains_prefix_of(&key),
+            (0..=key.len()).any(|len| oracle.contains(&key[..len]))
+        );
+    }
+
+    let other_oracle = BTreeSet::from_iter(b.iter().map(small));
+    let other: TrieSet<u8, ByteBranch<()>> = TrieSet::from_iter(other_oracle.iter().cloned());
+    let collect = |set: TrieSet<u8, ByteBranch<()>>| BTreeSet::from_iter(set.iter());
+    assert_eq!(collect(set.union(&other)), &oracle | &other_oracle);
+    assert_eq!(collect(set.intersection(&other)), &oracle & &other_oracle);
+    assert_eq!(collect(set.difference(&other)), &oracle - &other_oracle);
+    assert_eq!(
+        collect(set.symmetric_difference(&other)),
+        &oracle ^ &other_oracle
+    );
+    assert_eq!(set.is_subset(&other), oracle.is_subset(&other_oracle));
+    assert_eq!(set.is_disjoint(&other), oracle.is_disjoint(&other_oracle));
+    assert_eq!(set == other, oracle == other_oracle);
+    assert!(set.intersection(&other).is_subset(&set));
+    assert!(set.is_subset(&set.union(&other)));
+    assert!(set.difference(&other).is_disjoint(&other));
+}
@@ -0,0 +1,55 @@
+use buffman_tree::{
+    Trie,
+    branch::{BTreeBranch, Branch, ByteBranch, HashBranch, HeapSize},
+};
+use quickcheck_macros::quickcheck;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[test]
+fn stats() {
+    let trie: Trie<u8, u32, ByteBranch<u32>> = Trie::from_iter([
+        (b"foo".to_vec(), 1),
+        (b"foobar".to_vec(), 2),
+        (b"fox".to_vec(), 3),
+    ]);
+    let stats = trie.stats();
+    assert_eq!(stats.leaves.live, 3);
+    assert_eq!(stats.nodes.live, 4);
+    assert_eq!(stats.branches.live, 2);
+    assert!(stats.nodes.capacity >= stats.nodes.live);
+    assert_eq!(stats.prefix_lengths, BTreeMap::from([(0, 2), (2, 2)]));
+    assert_eq!(stats.fan_out, BTreeMap::from([(1, 1), (2, 1)]));
+    assert_eq!(stats.depths, BTreeMap::from([(1, 2), (2, 1)]));
+    assert!(stats.heap_bytes >= stats.branches.capacity * size_of::<ByteBranch<u32>>());
+
+    let hashed: Trie<u8, u32, HashBranch<u8, u32>> = Trie::from_iter([(b"a".to_vec(), 1)]);
+    assert_eq!(hashed.stats().depths, BTreeMap::from([(0, 1)]));
+}
+
+#[quickcheck]
+fn stats_oracle(keys: BTreeSet<Vec<u8>>) {
+    fn test<B: Branch<u8, ()> + HeapSize>(keys: &BTreeSet<Vec<u8>>) {
+        let trie: Trie<u8, (), B> = Trie::from_iter(keys.iter().map(|key| (key.clone(), ())));
+        let stats = trie.stats();
+        let total = |histogram: &BTreeMap<usize, usize>| histogram.values().sum::<usize>();
+        assert_eq!(stats.leaves.live, keys.len());
+        assert_eq!(total(&stats.depths), keys.len());
+        assert_eq!(total(&stats.prefix_lengths), stats.nodes.live);
+        assert_eq!(total(&stats.fan_out), stats.branches.live);
+        assert_eq!(
+            stats
+                .fan_out
+                .iter()
+                .map(|(n, count)| n * count)
+                .sum::<usize>(),
+            stats.nodes.live - 1
+        );
+    }
+    let keys = BTreeSet::from_iter(
+        keys.into_iter()
+            .map(|key| Vec::from_iter(key.into_iter().take(4).map(|b| b % 3))),
+    );
+    test::<BTreeBranch<_, _>>(&keys);
+    test::<ByteBranch<_>>(&keys);
+    test::<HashBranch<_, _>>(&keys);
+}
@@ -0,0 +1,56 @@
+use buffman_tree::{
+    branch::ByteBranch,
+    string::{CharTrie, StrTrie},
+};
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeMap;
+
+#[test]
+fn str_trie() {
+    let mut trie: StrTrie<usize> =
+        StrTrie::from_iter([("foo", 0), ("foobar", 1), ("føø", 2), ("bar", 3)]);
+    assert_eq!(trie.get("foo"), Some(&0));
+    assert_eq!(trie.get("fo"), None);
+    assert_eq!(trie.get_deepest("foobaz"), Some(&0));
+    assert_eq!(trie.get_deepest("fo"), None);
+    assert!(trie.starts_with("f"));
+    assert!(trie.starts_with("føø"));
+    assert!(!trie.starts_with("baz"));
+    assert!(trie.starts_with(""));
+    assert_eq!(
+        Vec::from_iter(trie.with_prefix("fo")),
+        [("foo".to_owned(), &0), ("foobar".to_owned(), &1)]
+    );
+    assert_eq!(Vec::from_iter(trie.keys()), ["bar", "foo", "foobar", "føø"]);
+    assert_eq!(trie.remove("foo"), Some(0));
+    assert_eq!(trie.get_deepest("foobaz"), None);
+    assert_eq!(trie.len(), 3);
+}
+
+#[test]
+fn char_trie() {
+    let trie: CharTrie<usize> = CharTrie::from_iter([("føø", 0), ("fø", 1), ("ƒ", 2)]);
+    assert_eq!(trie.get("fø"), Some(&1));
+    assert_eq!(trie.get_deepest("føx"), Some(&1));
+    assert_eq!(
+        Vec::from_iter(trie.with_prefix("f")),
+        [("fø".to_owned(), &1), ("føø".to_owned(), &0)]
+    );
+    assert_eq!(Vec::from_iter(trie.keys()), ["fø", "føø", "ƒ"]);
+}
+
+#[quickcheck]
+fn keys_roundtrip(data: BTreeMap<String, usize>, prefix: String) {
+    let bytes: StrTrie<usize, ByteBranch<_>> =
+        StrTrie::from_iter(data.iter().map(|(k, v)| (&**k, *v)));
+    let chars: CharTrie<usize> = CharTrie::from_iter(data.iter().map(|(k, v)| (&**k, *v)));
+    let expected = Vec::from_iter(data.iter().map(|(k, v)| (k.clone(), v)));
+    assert_eq!(Vec::from_iter(bytes.iter()), expected);
+    assert_eq!(Vec::from_iter(chars.iter()), expected);
+
+    let expected = Vec::from_iter(expected.into_iter().filter(|(k, _)| k.starts_with(&prefix)));
+    assert_eq!(Vec::from_iter(bytes.with_prefix(&prefix)), expected);
+    assert_eq!(Vec::from_iter(chars.with_prefix(&prefix)), expected);
+    assert_eq!(bytes.starts_with(&prefix), !expected.is_empty());
+    assert_eq!(chars.starts_with(&prefix), !expected.is_empty());
+}
@@ -0,0 +1,74 @@
+use buffman_tree::Trie;
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeMap;
+
+#[test]
+fn tokenize() {
+    let trie: Trie<u8, (Vec<u8>, &str)> = Trie::from_iter([
+        (b"let".to_vec(), "kw"),
+        (b"le".to_vec(), "le"),
+        (b"=".to_vec(), "eq"),
+        (b"==".to_vec(), "eqeq"),
+        (b" ".to_vec(), "ws"),
+        (b"".to_vec(), "empty"),
+    ]);
+    let tokens = Vec::from_iter(trie.tokenize(b"let x == lex").map(|token| {
+        token
+            .map(|(len, (_, kind))| (len, *kind))
+            .map_err(|c| *c as char)
+    }));
+    assert_eq!(
+        tokens,
+        [
+            Ok((3, "kw")),
+            Ok((1, "ws")),
+            Err('x'),
+            Ok((1, "ws")),
+            Ok((2, "eqeq")),
+            Ok((1, "ws")),
+            Ok((2, "le")),
+            Err('x'),
+        ]
+    );
+    assert_eq!(trie.tokenize(b"").count(), 0);
+}
+
+#[quickcheck]
+fn tokenize_oracle(keys: Vec<Vec<u8>>, input: Vec<u8>) {
+    let small = |bytes: Vec<u8>| Vec::from_iter(bytes.into_iter().map(|b| b % 4));
+    let keys = BTreeMap::from_iter(
+        keys.into_iter()
+            .map(small)
+            .filter(|key| !key.is_empty())
+            .map(|key| (key, ())),
+    );
+    let input = small(input);
+    let trie: Trie<u8, (Vec<u8>, ())> = Trie::from_iter(keys.clone());
+
+    let mut expected = vec![];
+    let mut rest = &input[..];
+    while !rest.is_empty() {
+        match (1..=rest.len())
+            .rev()
+            .find(|len| keys.contains_key(&rest[..*len]))
+        {
+            Some(len) => {
+                expected.push(Ok(rest[..len].to_vec()));
+                rest = &rest[len..];
+            }
+            None => {
+                expected.push(Err(rest[0]));
+                rest = &rest[1..];
+            }
+        }
+    }
+    let tokens = Vec::from_iter(trie.tokenize(&input).map(|token| {
+        token
+            .map(|(len, (key, ()))| {
+                assert_eq!(len, key.len());
+                key.clone()
+            })
+            .map_err(|e| *e)
+    }));
+    assert_eq!(tokens, expected);
+}
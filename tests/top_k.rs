@@ -0,0 +1,88 @@
+use buffman_tree::Trie;
+use quickcheck_macros::quickcheck;
+use std::{cmp::Reverse, collections::BTreeMap};
+
+#[test]
+fn completions() {
+    let trie: Trie<u8, (Vec<u8>, u32)> = Trie::from_iter([
+        (b"car".to_vec(), 30),
+        (b"card".to_vec(), 5),
+        (b"care".to_vec(), 50),
+        (b"carpet".to_vec(), 20),
+        (b"cat".to_vec(), 90),
+        (b"dog".to_vec(), 100),
+    ]);
+    let words = |values: Vec<&(Vec<u8>, u32)>| {
+        Vec::from_iter(
+            values
+                .into_iter()
+                .map(|(key, _)| String::from_utf8(key.clone()).unwrap()),
+        )
+    };
+    assert_eq!(
+        words(trie.top_k(b"car", 2, |(_, score)| *score)),
+        ["care", "car"]
+    );
+    assert_eq!(
+        words(trie.top_k(b"ca", 10, |(_, score)| *score)),
+        ["cat", "care", "car", "carpet", "card"]
+    );
+    assert_eq!(trie.top_k(b"cab", 3, |(_, score)| *score).len(), 0);
+    assert_eq!(trie.top_k(b"", 0, |(_, score)| *score).len(), 0);
+
+    let mut index = trie.into_score_index(|(_, score): &(Vec<u8>, u32)| *score);
+    assert_eq!(words(index.top_k(b"car", 2)), ["care", "car"]);
+    assert_eq!(words(index.top_k(b"c", 1)), ["cat"]);
+    assert_eq!(words(index.top_k(b"", 2)), ["dog", "cat"]);
+    assert_eq!(words(index.top_k(b"carp", 5)), ["carpet"]);
+    assert_eq!(index.top_k(b"x", 5).len(), 0);
+
+    index.insert(b"cart".to_vec(), (b"cart".to_vec(), 95));
+    assert_eq!(words(index.top_k(b"", 2)), ["dog", "cart"]);
+    assert_eq!(index.remove(b"dog"), Some((b"dog".to_vec(), 100)));
+    assert_eq!(index.remove(b"cat"), Some((b"cat".to_vec(), 90)));
+    assert_eq!(words(index.top_k(b"", 3)), ["cart", "care", "car"]);
+    assert_eq!(words(index.top_k(b"ca", 1)), ["cart"]);
+}
+
+#[quickcheck]
+fn top_k_oracle(ops: Vec<(bool, Vec<u8>, u8)>, prefix: Vec<u8>, k: u8) {
+    let small = |bytes: Vec<u8>| Vec::from_iter(bytes.into_iter().take(4).map(|b| b % 3));
+    let prefix = Vec::from_iter(small(prefix).into_iter().take(2));
+    let k = k as usize % 8;
+    let mut index = Trie::<u8, (Vec<u8>, u8)>::default().into_score_index(|(_, score)| *score);
+    let mut entries = BTreeMap::new();
+    for (insert, key, score) in ops {
+        let key = small(key);
+        if insert {
+            assert_eq!(
+                index.insert(key.clone(), (key.clone(), score)),
+                entries.insert(key.clone(), score).map(|old| (key, old))
+            );
+        } else {
+            assert_eq!(
+                index.remove(&key),
+                entries.remove(&key).map(|old| (key, old))
+            );
+        }
+    }
+
+    let mut expected = Vec::from_iter(
+        entries
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .map(|(key, score)| (key.clone(), *score)),
+    );
+    expected.sort_by_key(|(_, score)| Reverse(*score));
+    expected.truncate(k);
+
+    let scanned = index.as_trie().top_k(&prefix, k, |(_, score)| *score);
+    assert_eq!(Vec::from_iter(scanned.into_iter().cloned()), expected);
+
+    let searched = index.top_k(&prefix, k);
+    assert_eq!(
+        Vec::from_iter(searched.iter().map(|(_, score)| *score)),
+        Vec::from_iter(expected.iter().map(|(_, score)| *score))
+    );
+    assert!(searched.iter().all(|(key, _)| key.starts_with(&prefix)));
+}